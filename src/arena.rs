@@ -0,0 +1,190 @@
+use std::ops::{Index, IndexMut};
+
+/// Refers to a value in an [`Arena`], and stops resolving once that value is
+/// removed, even if its slot gets reused.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+/// Stores values in reusable slots, handing out generational handles.
+#[derive(Clone, Debug)]
+pub struct Arena<T> {
+    free: Vec<u32>,
+    len: usize,
+    slots: Vec<Slot<T>>,
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self {
+            free: vec![],
+            len: 0,
+            slots: vec![],
+        }
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let slot = self.slots.get(handle.index as usize)?;
+        match slot.generation == handle.generation {
+            true => slot.value.as_ref(),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        match slot.generation == handle.generation {
+            true => slot.value.as_mut(),
+            false => None,
+        }
+    }
+
+    /// Lists current handles, so values can be changed or removed while
+    /// walking through them.
+    pub fn handles(&self) -> Vec<Handle> {
+        self.iter().map(|(handle, _)| handle).collect()
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+                Handle {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                Handle {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates values with their handles, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let handle = Handle {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Removes the value if the handle is still current, retiring the handle.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(Handle, &T) -> bool) {
+        for handle in self.handles() {
+            if !keep(handle, self.get(handle).unwrap()) {
+                self.remove(handle);
+            }
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Handle> for Arena<T> {
+    type Output = T;
+
+    /// Panics if the handle is stale.
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("stale handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for Arena<T> {
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("stale handle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_handles_stay_stale() {
+        let mut arena = Arena::new();
+        let old = arena.insert("old");
+        assert_eq!(arena.remove(old), Some("old"));
+        // The new value takes the freed slot, but the old handle still fails.
+        let new = arena.insert("new");
+        assert_eq!(new.index, old.index);
+        assert_ne!(new, old);
+        assert_eq!(arena.get(old), None);
+        assert_eq!(arena.get_mut(old), None);
+        assert!(!arena.contains(old));
+        assert_eq!(arena.remove(old), None);
+        assert_eq!(arena[new], "new");
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn retain_keeps_len() {
+        let mut arena = Arena::new();
+        let handles: Vec<Handle> = (0..10).map(|value| arena.insert(value)).collect();
+        arena.retain(|_, &value| value % 3 == 0);
+        assert_eq!(arena.len(), 4);
+        assert_eq!(arena.values().copied().collect::<Vec<_>>(), [0, 3, 6, 9]);
+        assert!(!arena.contains(handles[1]));
+        assert!(arena.contains(handles[3]));
+        arena.retain(|_, _| false);
+        assert!(arena.is_empty());
+        assert_eq!(arena.iter().count(), 0);
+        // Freed slots refill without disturbing the count.
+        for value in 0..3 {
+            arena.insert(value);
+        }
+        assert_eq!(arena.len(), 3);
+        assert_eq!(arena.slots.len(), 10);
+    }
+}
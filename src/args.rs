@@ -0,0 +1,55 @@
+use clap::Parser;
+use macroquad::prelude::*;
+use std::path::PathBuf;
+
+use greeny::cli::{parse_pos, parse_size};
+
+use crate::assets::{ASSET_DIR, HOT_RELOAD};
+
+/// Wander a desert island of cactus and critters.
+///
+/// Continues from the last save unless any of the world options ask for a
+/// new one, which then leaves the save alone rather than writing over it.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// Load art from this directory instead of what's built in, and reload
+    /// it on change.
+    #[arg(long, value_name = "DIR")]
+    pub assets: Option<PathBuf>,
+    /// Start in fullscreen.
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Start a new world from this seed, or from the clock if only other
+    /// world options are given.
+    #[arg(long, help_heading = "World")]
+    pub seed: Option<u64>,
+    /// Start a new world of this many tiles across and down.
+    #[arg(long, value_name = "WxH", value_parser = parse_size, help_heading = "World")]
+    pub size: Option<IVec2>,
+    /// Put the player on this tile rather than where they were or the middle
+    /// of the world.
+    #[arg(long, value_name = "X,Y", value_parser = parse_pos)]
+    pub start: Option<IVec2>,
+    /// Window size in pixels, when not fullscreen.
+    #[arg(long, value_name = "WxH", value_parser = parse_size, default_value = "840x480")]
+    pub window: IVec2,
+    /// Start a new world that wraps around at its edges.
+    #[arg(long, help_heading = "World")]
+    pub wrap: bool,
+}
+
+impl Args {
+    /// Where to load assets from disk, if anywhere.
+    pub fn asset_dir(&self) -> Option<PathBuf> {
+        match &self.assets {
+            Some(dir) => Some(dir.clone()),
+            None => HOT_RELOAD.then(|| ASSET_DIR.into()),
+        }
+    }
+
+    /// Whether any option asks for a new world rather than the saved one.
+    pub fn new_world(&self) -> bool {
+        self.seed.is_some() || self.size.is_some() || self.wrap
+    }
+}
@@ -1,71 +1,154 @@
 use macroquad::prelude::*;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::info::GameMetrics;
+use greeny::atlas::{Atlas, AtlasError, TileInfo};
+use greeny::info::GameMetrics;
 
 pub struct Assets {
+    /// Newest change among the files on disk, when loaded from there.
+    pub changed: Option<SystemTime>,
+    /// Where the files were loaded from, or `None` if built in.
+    pub dir: Option<PathBuf>,
     pub icons: Font,
     pub mountains: Texture2D,
     pub tile_info: TileInfo,
     pub tiles: Texture2D,
 }
 
+#[derive(Debug)]
+pub enum AssetError {
+    Atlas(AtlasError),
+    Decode(String, macroquad::Error),
+    Io(String, io::Error),
+}
+
+/// An asset embedded in the binary, with its path under the assets dir.
+pub struct AssetFile {
+    pub embedded: &'static [u8],
+    pub path: &'static str,
+}
+
 impl Assets {
-    pub fn load(game_metrics: &GameMetrics) -> Self {
-        Assets {
-            icons: load_ttf_font_from_bytes(ICONS).unwrap(),
-            mountains: load_texture(MOUNTAINS),
-            tile_info: TileInfo::new(game_metrics),
-            tiles: load_texture(TILES),
+    /// Loads from files in `dir` if given, or else the ones built in.
+    pub fn load(dir: Option<&Path>, game_metrics: &GameMetrics) -> Result<Self, AssetError> {
+        let changed = dir.and_then(newest_change);
+        let tiles = load_texture(&TILES, dir)?;
+        let atlas = Atlas::parse(&ATLAS.read(dir)?, game_metrics.tile_size_px, tiles.size())?;
+        let icons = load_ttf_font_from_bytes(&ICONS.read(dir)?)
+            .map_err(|err| AssetError::Decode(ICONS.path.to_owned(), err))?;
+        Ok(Assets {
+            changed,
+            dir: dir.map(Path::to_path_buf),
+            icons,
+            mountains: load_texture(&MOUNTAINS, dir)?,
+            tile_info: TileInfo::new(&atlas)?,
+            tiles,
+        })
+    }
+
+    /// Reloads everything after any file changes on disk, keeping the old
+    /// assets if the new ones fail, such as from a half-saved file. Returns
+    /// whether anything was swapped in.
+    pub fn reload_changed(&mut self, game_metrics: &GameMetrics) -> bool {
+        let Some(dir) = self.dir.clone() else {
+            return false;
+        };
+        let changed = newest_change(&dir);
+        if changed == self.changed {
+            return false;
+        }
+        // Only try each change once, rather than warning every frame.
+        self.changed = changed;
+        match Assets::load(Some(&dir), game_metrics) {
+            Ok(assets) => {
+                *self = assets;
+                info!("Reloaded assets");
+                true
+            }
+            Err(err) => {
+                warn!("Keeping old assets: {}", err);
+                false
+            }
         }
     }
 }
 
-#[allow(unused)]
-pub struct TileInfo {
-    pub bead: Vec2,
-    pub bob: Vec2,
-    pub coyote: Vec2,
-    pub jack: Vec2,
-    pub javelina: Vec2,
-    pub nopal_big: Rect,
-    pub nopal_small: Rect,
-    pub ocotillo: Rect,
-    pub rattler: Vec2,
-    pub runner: Vec2,
-    pub saguaro: Rect,
-    pub turkey: Vec2,
+impl AssetFile {
+    /// Reads from under `dir` if given, or else from the binary.
+    pub fn read(&self, dir: Option<&Path>) -> Result<Cow<'static, [u8]>, AssetError> {
+        match dir {
+            Some(dir) => fs::read(dir.join(self.path))
+                .map(Cow::Owned)
+                .map_err(|err| AssetError::Io(self.path.to_owned(), err)),
+            None => Ok(Cow::Borrowed(self.embedded)),
+        }
+    }
 }
 
-impl TileInfo {
-    pub fn new(game_metrics: &GameMetrics) -> Self {
-        let rect = |x: i32, y: i32, w: i32, h: i32| -> Rect {
-            let pos = Vec2::new(x as f32, y as f32) * game_metrics.tile_size_px;
-            let size = Vec2::new(w as f32, h as f32) * game_metrics.tile_size_px;
-            Rect::new(pos.x, pos.y, size.x, size.y)
-        };
-        Self {
-            bead: Vec2::new(8.0, 10.0) * game_metrics.tile_size_px,
-            bob: Vec2::new(6.0, 9.0) * game_metrics.tile_size_px,
-            coyote: Vec2::new(6.0, 10.0) * game_metrics.tile_size_px,
-            jack: Vec2::new(7.0, 9.0) * game_metrics.tile_size_px,
-            javelina: Vec2::new(5.0, 10.0) * game_metrics.tile_size_px,
-            nopal_big: rect(6, 7, 3, 2),
-            nopal_small: rect(4, 7, 1, 1),
-            ocotillo: rect(11, 6, 3, 3),
-            rattler: Vec2::new(7.0, 10.0) * game_metrics.tile_size_px,
-            runner: Vec2::new(8.0, 9.0) * game_metrics.tile_size_px,
-            saguaro: rect(1, 4, 3, 5),
-            turkey: Vec2::new(5.0, 9.0) * game_metrics.tile_size_px,
+pub fn load_texture(file: &AssetFile, dir: Option<&Path>) -> Result<Texture2D, AssetError> {
+    let image = Image::from_file_with_format(&file.read(dir)?, None)
+        .map_err(|err| AssetError::Decode(file.path.to_owned(), err))?;
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    Ok(texture)
+}
+
+/// Latest modified time of any asset file, if all can be checked.
+fn newest_change(dir: &Path) -> Option<SystemTime> {
+    FILES
+        .iter()
+        .map(|file| fs::metadata(dir.join(file.path))?.modified())
+        .collect::<io::Result<Vec<_>>>()
+        .ok()?
+        .into_iter()
+        .max()
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetError::Atlas(err) => write!(f, "{err}"),
+            AssetError::Decode(path, err) => write!(f, "can't decode {path}: {err}"),
+            AssetError::Io(path, err) => write!(f, "can't read {path}: {err}"),
         }
     }
 }
 
-pub fn load_texture(bytes: &[u8]) -> Texture2D {
-    let texture = Texture2D::from_file_with_format(bytes, None);
-    texture.set_filter(FilterMode::Nearest);
-    texture
+impl std::error::Error for AssetError {}
+
+impl From<AtlasError> for AssetError {
+    fn from(err: AtlasError) -> Self {
+        AssetError::Atlas(err)
+    }
 }
 
-pub const ICONS: &[u8] = include_bytes!("../assets/fontello/fontello.ttf");
-pub const MOUNTAINS: &[u8] = include_bytes!("../assets/mountains.png");
-pub const TILES: &[u8] = include_bytes!("../assets/distinct.png");
+/// Whether to load assets from the source dir by default and watch them for
+/// changes, for development, rather than use those built in.
+pub const HOT_RELOAD: bool = cfg!(feature = "hot-reload");
+
+/// The source assets dir, for hot reloading.
+pub const ASSET_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+
+pub const ATLAS: AssetFile = AssetFile {
+    embedded: include_bytes!("../assets/atlas.json"),
+    path: "atlas.json",
+};
+pub const ICONS: AssetFile = AssetFile {
+    embedded: include_bytes!("../assets/fontello/fontello.ttf"),
+    path: "fontello/fontello.ttf",
+};
+pub const MOUNTAINS: AssetFile = AssetFile {
+    embedded: include_bytes!("../assets/mountains.png"),
+    path: "mountains.png",
+};
+pub const TILES: AssetFile = AssetFile {
+    embedded: include_bytes!("../assets/distinct.png"),
+    path: "distinct.png",
+};
+
+const FILES: [&AssetFile; 4] = [&ATLAS, &ICONS, &MOUNTAINS, &TILES];
@@ -0,0 +1,302 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::world::{AnimalKind, Plant};
+
+/// Describes where each sprite sits in the tile sheet, read from a manifest
+/// so art can move without code changes.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    pub sprites: BTreeMap<String, Sprite>,
+}
+
+/// A sprite's place in the sheet, all in pixels.
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    /// Offset from the sprite's corner to the tile it stands on.
+    pub anchor: Vec2,
+    /// Source rects of each animation frame, in order, with at least one.
+    pub frames: Vec<Rect>,
+}
+
+#[derive(Debug)]
+pub enum AtlasError {
+    Anchor(String),
+    Empty(String),
+    Format(serde_json::Error),
+    Missing(String),
+    Outside(String),
+    Overlap(String, String),
+}
+
+/// Manifest entry, in tiles.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpriteEntry {
+    /// Corner and size, as `[x, y, width, height]`.
+    rect: [u32; 4],
+    /// Defaults to the bottom middle of the rect.
+    #[serde(default)]
+    anchor: Option<[u32; 2]>,
+    /// Corners of each frame, if animated, all the size of the rect.
+    #[serde(default)]
+    frames: Vec<[u32; 2]>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    sprites: BTreeMap<String, SpriteEntry>,
+}
+
+impl Atlas {
+    /// Reads and checks a JSON manifest against the sheet it describes.
+    pub fn parse(bytes: &[u8], tile_size: Vec2, image_size: Vec2) -> Result<Self, AtlasError> {
+        let manifest: Manifest = serde_json::from_slice(bytes)?;
+        let image = Rect::new(0.0, 0.0, image_size.x, image_size.y);
+        let mut sprites = BTreeMap::new();
+        for (name, entry) in manifest.sprites {
+            let [x, y, w, h] = entry.rect.map(|n| n as f32);
+            let size = vec2(w, h);
+            let anchor = match entry.anchor {
+                Some([x, y]) => vec2(x as f32, y as f32),
+                None => ((size - 1.0) * vec2(0.5, 1.0)).floor(),
+            };
+            if w == 0.0 || h == 0.0 {
+                return Err(AtlasError::Empty(name));
+            }
+            if anchor.cmpge(size).any() {
+                return Err(AtlasError::Anchor(name));
+            }
+            let corners = match entry.frames.is_empty() {
+                true => vec![vec2(x, y)],
+                false => entry
+                    .frames
+                    .iter()
+                    .map(|&[x, y]| vec2(x as f32, y as f32))
+                    .collect(),
+            };
+            let frames: Vec<Rect> = corners
+                .into_iter()
+                .map(|corner| {
+                    let pos = corner * tile_size;
+                    let size = size * tile_size;
+                    Rect::new(pos.x, pos.y, size.x, size.y)
+                })
+                .collect();
+            if frames.iter().any(|frame| !contains(image, *frame)) {
+                return Err(AtlasError::Outside(name));
+            }
+            let anchor = anchor * tile_size;
+            sprites.insert(name, Sprite { anchor, frames });
+        }
+        // Frames of the same sprite may repeat, but sprites shouldn't share
+        // pixels.
+        let named: Vec<_> = sprites.iter().collect();
+        for (index, (name, sprite)) in named.iter().enumerate() {
+            for (other_name, other) in &named[index + 1..] {
+                let overlaps = sprite.frames.iter().any(|frame| {
+                    other.frames.iter().any(|other| {
+                        frame
+                            .intersect(*other)
+                            .is_some_and(|shared| shared.w * shared.h > 0.0)
+                    })
+                });
+                if overlaps {
+                    return Err(AtlasError::Overlap(
+                        name.to_string(),
+                        other_name.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(Self { sprites })
+    }
+
+    pub fn sprite(&self, name: &str) -> Result<Sprite, AtlasError> {
+        self.sprites
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AtlasError::Missing(name.to_owned()))
+    }
+}
+
+impl Sprite {
+    /// The first or standing frame.
+    pub fn rect(&self) -> Rect {
+        self.frames[0]
+    }
+}
+
+/// The sprites for everything in the world, pulled by name from the atlas.
+pub struct TileInfo {
+    pub bead: Sprite,
+    pub bob: Sprite,
+    pub coyote: Sprite,
+    pub jack: Sprite,
+    pub javelina: Sprite,
+    /// Size of the largest sprite.
+    pub largest: Vec2,
+    pub nopal_big: Sprite,
+    pub nopal_small: Sprite,
+    pub ocotillo: Sprite,
+    pub rattler: Sprite,
+    /// Also the player, with its frames as the walk cycle.
+    pub runner: Sprite,
+    pub saguaro: Sprite,
+    pub turkey: Sprite,
+}
+
+impl TileInfo {
+    pub fn new(atlas: &Atlas) -> Result<Self, AtlasError> {
+        let largest = atlas
+            .sprites
+            .values()
+            .map(|sprite| sprite.rect().size())
+            .fold(Vec2::ZERO, Vec2::max);
+        Ok(Self {
+            bead: atlas.sprite("bead")?,
+            bob: atlas.sprite("bob")?,
+            coyote: atlas.sprite("coyote")?,
+            jack: atlas.sprite("jack")?,
+            javelina: atlas.sprite("javelina")?,
+            largest,
+            nopal_big: atlas.sprite("nopal_big")?,
+            nopal_small: atlas.sprite("nopal_small")?,
+            ocotillo: atlas.sprite("ocotillo")?,
+            rattler: atlas.sprite("rattler")?,
+            runner: atlas.sprite("runner")?,
+            saguaro: atlas.sprite("saguaro")?,
+            turkey: atlas.sprite("turkey")?,
+        })
+    }
+}
+
+impl TileInfo {
+    pub fn animal(&self, kind: AnimalKind) -> &Sprite {
+        match kind {
+            AnimalKind::Bead => &self.bead,
+            AnimalKind::Bob => &self.bob,
+            AnimalKind::Coyote => &self.coyote,
+            AnimalKind::Jack => &self.jack,
+            AnimalKind::Javelina => &self.javelina,
+            AnimalKind::Rattler => &self.rattler,
+            AnimalKind::Runner => &self.runner,
+            AnimalKind::Turkey => &self.turkey,
+        }
+    }
+
+    pub fn plant(&self, plant: Plant) -> &Sprite {
+        match plant {
+            Plant::NopalBig => &self.nopal_big,
+            Plant::NopalSmall => &self.nopal_small,
+            Plant::Ocotillo => &self.ocotillo,
+            Plant::Saguaro => &self.saguaro,
+        }
+    }
+}
+
+fn contains(outer: Rect, inner: Rect) -> bool {
+    inner.left() >= outer.left()
+        && inner.top() >= outer.top()
+        && inner.right() <= outer.right()
+        && inner.bottom() <= outer.bottom()
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AtlasError::Anchor(name) => write!(f, "sprite {name} has its anchor outside it"),
+            AtlasError::Empty(name) => write!(f, "sprite {name} has no size"),
+            AtlasError::Format(err) => write!(f, "bad atlas format: {err}"),
+            AtlasError::Missing(name) => write!(f, "atlas has no sprite {name}"),
+            AtlasError::Outside(name) => write!(f, "sprite {name} runs off the sheet"),
+            AtlasError::Overlap(a, b) => write!(f, "sprites {a} and {b} overlap"),
+        }
+    }
+}
+
+impl std::error::Error for AtlasError {}
+
+impl From<serde_json::Error> for AtlasError {
+    fn from(err: serde_json::Error) -> Self {
+        AtlasError::Format(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::info::GameMetrics;
+
+    /// Parses sprites for a 4 by 3 tile sheet of 10 by 9 pixel tiles.
+    fn parse(sprites: &str) -> Result<Atlas, AtlasError> {
+        let json = format!(r#"{{"sprites": {{{sprites}}}}}"#);
+        Atlas::parse(json.as_bytes(), vec2(10.0, 9.0), vec2(40.0, 27.0))
+    }
+
+    #[test]
+    fn parses_pixels_and_default_anchor() {
+        let atlas = parse(
+            r#""tall": {"rect": [1, 0, 1, 3]},
+            "walk": {"rect": [2, 0, 2, 1], "anchor": [1, 0], "frames": [[2, 0], [2, 1]]}"#,
+        )
+        .unwrap();
+        let tall = atlas.sprite("tall").unwrap();
+        assert_eq!(tall.rect(), Rect::new(10.0, 0.0, 10.0, 27.0));
+        assert_eq!(tall.anchor, vec2(0.0, 18.0));
+        let walk = atlas.sprite("walk").unwrap();
+        assert_eq!(walk.anchor, vec2(10.0, 0.0));
+        assert_eq!(walk.frames[1], Rect::new(20.0, 9.0, 20.0, 9.0));
+    }
+
+    #[test]
+    fn rejects_bad_sprites() {
+        let err = |sprites| parse(sprites).unwrap_err();
+        assert!(matches!(
+            parse("").unwrap().sprite("gone"),
+            Err(AtlasError::Missing(name)) if name == "gone"
+        ));
+        assert!(matches!(
+            err(r#""a": {"rect": [0, 0, 2, 2]}, "b": {"rect": [1, 1, 1, 1]}"#),
+            AtlasError::Overlap(a, b) if a == "a" && b == "b"
+        ));
+        assert!(matches!(
+            err(r#""wide": {"rect": [3, 0, 2, 1]}"#),
+            AtlasError::Outside(name) if name == "wide"
+        ));
+        assert!(matches!(
+            err(r#""step": {"rect": [0, 0, 1, 1], "frames": [[0, 3]]}"#),
+            AtlasError::Outside(name) if name == "step"
+        ));
+        assert!(matches!(
+            err(r#""off": {"rect": [0, 0, 1, 1], "anchor": [0, 1]}"#),
+            AtlasError::Anchor(name) if name == "off"
+        ));
+        assert!(matches!(
+            err(r#""flat": {"rect": [0, 0, 1, 0]}"#),
+            AtlasError::Empty(name) if name == "flat"
+        ));
+        assert!(matches!(
+            err(r#""typo": {"rect": [0, 0, 1, 1], "ancor": [0, 0]}"#),
+            AtlasError::Format(_)
+        ));
+    }
+
+    #[test]
+    fn shipped_atlas_fits_sheet() {
+        let sheet = Image::from_file_with_format(
+            include_bytes!("../assets/distinct.png"),
+            Some(ImageFormat::Png),
+        )
+        .unwrap();
+        let size = vec2(sheet.width as f32, sheet.height as f32);
+        let tile_size = GameMetrics::default().tile_size_px;
+        let atlas = Atlas::parse(include_bytes!("../assets/atlas.json"), tile_size, size).unwrap();
+        let tile_info = TileInfo::new(&atlas).unwrap();
+        // The player needs a walk cycle beyond standing still.
+        assert!(tile_info.runner.frames.len() > 1);
+    }
+}
@@ -0,0 +1,75 @@
+//! Writes a whole world as generated to a PNG, without a window or GPU.
+
+use clap::Parser;
+use macroquad::prelude::*;
+use std::path::PathBuf;
+use std::process::exit;
+
+use greeny::atlas::{Atlas, TileInfo};
+use greeny::cli::parse_size;
+use greeny::info::GameMetrics;
+use greeny::map::{paint_sprites, paint_tiles};
+use greeny::world::World;
+
+const ATLAS: &[u8] = include_bytes!("../../assets/atlas.json");
+const TILES: &[u8] = include_bytes!("../../assets/distinct.png");
+
+/// Writes a whole world as generated to a PNG, with each tile a block colored
+/// by its plant, animal or biome, or drawn with the game's own art.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Where to write the PNG.
+    #[arg(long, default_value = "greeny-map.png")]
+    out: PathBuf,
+    /// Pixels across each tile's block, when not drawing sprites.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    scale: u16,
+    /// Seed for generating the world.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// World size in tiles across and down.
+    #[arg(long, value_name = "WxH", value_parser = parse_size, default_value = "1500x1100")]
+    size: IVec2,
+    /// Draw sprites at full size instead of a block per tile, which makes for
+    /// a big image, so maybe try a smaller world.
+    #[arg(long)]
+    sprites: bool,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    exit(1);
+}
+
+fn main() {
+    let args = Args::parse();
+    let world = World::generate(args.seed, Some(args.size));
+    let image = match args.sprites {
+        true => {
+            let sheet = Image::from_file_with_format(TILES, None)
+                .unwrap_or_else(|err| fail(&format!("Can't decode the tiles: {err}")));
+            let tile_size = GameMetrics::new().tile_size_px;
+            let size = vec2(sheet.width as f32, sheet.height as f32);
+            let tile_info = Atlas::parse(ATLAS, tile_size, size)
+                .and_then(|atlas| TileInfo::new(&atlas))
+                .unwrap_or_else(|err| fail(&format!("Bad atlas: {err}")));
+            paint_sprites(&world, &sheet, &tile_info, tile_size)
+        }
+        false => paint_tiles(&world, args.scale),
+    };
+    let Some(image) = image else {
+        fail("The map is too big for an image, so try a smaller size or scale");
+    };
+    let saved = image::save_buffer(
+        &args.out,
+        &image.bytes,
+        image.width as u32,
+        image.height as u32,
+        image::ColorType::Rgba8,
+    );
+    if let Err(err) = saved {
+        fail(&format!("Can't write {}: {err}", args.out.display()));
+    }
+    eprintln!("Wrote {}", args.out.display());
+}
@@ -0,0 +1,59 @@
+//! Runs a world without a window, printing how it turned out as JSON.
+
+use clap::Parser;
+use macroquad::prelude::*;
+use std::time::Instant;
+
+use greeny::cli::parse_size;
+use greeny::world::{Edge, World};
+
+/// Runs a world for a while without a window, then prints stats about its
+/// plants and animals as JSON.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Chunks to load around the center, which the stats cover. The rest of
+    /// the world isn't generated, so raise this to count more of it.
+    #[arg(long, default_value_t = World::LOAD_RADIUS, value_parser = clap::value_parser!(i32).range(0..))]
+    radius: i32,
+    /// Seed for generating the world.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// World size in tiles across and down.
+    #[arg(long, value_name = "WxH", value_parser = parse_size, default_value = "1500x1100")]
+    size: IVec2,
+    /// Ticks to run, at four per second of game time.
+    #[arg(long, default_value_t = 2400)]
+    ticks: u64,
+    /// Wrap around at the world's edges.
+    #[arg(long)]
+    wrap: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let start = Instant::now();
+    let mut world = World::generate(args.seed, Some(args.size));
+    if args.wrap {
+        world.edge = Edge::Wrap;
+    }
+    // Stand in the middle, as a new game does, so the same chunks load by
+    // default.
+    let player = world.center();
+    world.load_chunks(player, args.radius);
+    for _ in 0..args.ticks {
+        world.tick(player);
+    }
+    eprintln!(
+        "Ran {} ticks in {:.3}ms",
+        args.ticks,
+        start.elapsed().as_secs_f64() * 1e3
+    );
+    match serde_json::to_string_pretty(&world.stats()) {
+        Ok(json) => println!("{json}"),
+        Err(err) => {
+            eprintln!("Failed to write stats: {err}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::noise::fractal2;
+use crate::world::{AnimalKind, Plant};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Biome {
+    /// Open, sparse flats between everything else.
+    CreosoteFlats,
+    /// Dry, sandy channels lined with thick prickly pear.
+    MesquiteWash,
+    /// Rocky high ground where ocotillo thrives.
+    OcotilloRidge,
+    /// Gentle slopes below the ridges, full of saguaro.
+    SaguaroBajada,
+}
+
+impl Biome {
+    /// Size in tiles of the broadest biome features.
+    const SCALE: f32 = 96.0;
+
+    /// Picks the biome from elevation and moisture noise fields, which vary
+    /// smoothly across the world for the seed.
+    pub fn at(seed: u64, pos: IVec2) -> Self {
+        let pos = pos.as_vec2() / Self::SCALE;
+        let elevation = fractal2(seed, pos, 3);
+        // Offset moisture so it doesn't line up with elevation.
+        let moisture = fractal2(seed ^ 0x6D6F_6973_7475_7265, pos + vec2(57.3, 91.7), 3);
+        match () {
+            _ if elevation > 0.62 => Biome::OcotilloRidge,
+            _ if moisture > 0.6 && elevation < 0.5 => Biome::MesquiteWash,
+            _ if elevation > 0.5 => Biome::SaguaroBajada,
+            _ => Biome::CreosoteFlats,
+        }
+    }
+
+    /// Relative odds of each animal kind spawning here.
+    pub fn animal_odds(self) -> &'static [(AnimalKind, f32)] {
+        use AnimalKind::*;
+        match self {
+            Biome::CreosoteFlats => &[
+                (Bead, 1.0),
+                (Bob, 0.5),
+                (Coyote, 2.0),
+                (Jack, 4.0),
+                (Javelina, 0.5),
+                (Rattler, 2.0),
+                (Runner, 2.0),
+                (Turkey, 0.5),
+            ],
+            Biome::MesquiteWash => &[
+                (Bead, 0.5),
+                (Bob, 1.0),
+                (Coyote, 1.0),
+                (Jack, 1.0),
+                (Javelina, 4.0),
+                (Rattler, 0.5),
+                (Runner, 1.0),
+                (Turkey, 3.0),
+            ],
+            Biome::OcotilloRidge => &[
+                (Bead, 2.0),
+                (Bob, 2.0),
+                (Coyote, 1.0),
+                (Jack, 1.0),
+                (Javelina, 0.5),
+                (Rattler, 3.0),
+                (Runner, 1.0),
+                (Turkey, 0.5),
+            ],
+            Biome::SaguaroBajada => &[
+                (Bead, 2.0),
+                (Bob, 1.0),
+                (Coyote, 1.0),
+                (Jack, 2.0),
+                (Javelina, 1.0),
+                (Rattler, 1.0),
+                (Runner, 3.0),
+                (Turkey, 1.0),
+            ],
+        }
+    }
+
+    /// Chance of each plant on a tile, with the remainder left empty.
+    pub fn plant_odds(self) -> &'static [(Plant, f32)] {
+        use Plant::*;
+        match self {
+            Biome::CreosoteFlats => &[
+                (NopalSmall, 0.05),
+                (NopalBig, 0.02),
+                (Ocotillo, 0.01),
+                (Saguaro, 0.005),
+            ],
+            Biome::MesquiteWash => &[
+                (NopalSmall, 0.12),
+                (NopalBig, 0.14),
+                (Ocotillo, 0.01),
+                (Saguaro, 0.01),
+            ],
+            Biome::OcotilloRidge => &[
+                (NopalSmall, 0.04),
+                (NopalBig, 0.02),
+                (Ocotillo, 0.14),
+                (Saguaro, 0.01),
+            ],
+            Biome::SaguaroBajada => &[
+                (NopalSmall, 0.06),
+                (NopalBig, 0.05),
+                (Ocotillo, 0.02),
+                (Saguaro, 0.09),
+            ],
+        }
+    }
+}
+
+/// Picks from odds scaled by `total`, or `None` if the roll lands past them.
+pub fn pick<T: Copy>(rng: &RandGenerator, odds: &[(T, f32)], total: f32) -> Option<T> {
+    let mut n = rng.gen_range(0.0, total);
+    for &(item, chance) in odds {
+        if n < chance {
+            return Some(item);
+        }
+        n -= chance;
+    }
+    None
+}
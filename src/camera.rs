@@ -0,0 +1,164 @@
+use macroquad::prelude::*;
+
+use crate::info::{GameMetrics, ScreenMetrics};
+
+/// Maps world tiles to the screen around a center, which is drawn where the
+/// player stands.
+///
+/// Ground rows run down the screen from the horizon. Rows beyond the horizon
+/// fold back down behind it, so farther things sink out of view, leaving only
+/// the tops of tall ones showing against the sky.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    /// World position drawn at the ground center.
+    pub center: Vec2,
+    /// In tiles.
+    pub ground_center: Vec2,
+    /// In screen pixels.
+    pub ground_start: Vec2,
+    /// In tiles.
+    pub ground_size: Vec2,
+    /// Size of the largest sprite in tiles, for how far past the ground
+    /// edges things can still show.
+    pub reach: Vec2,
+    /// In screen pixels.
+    pub tile_size: Vec2,
+}
+
+impl Camera {
+    pub fn new(
+        center: Vec2,
+        reach: Vec2,
+        game_metrics: &GameMetrics,
+        screen_metrics: &ScreenMetrics,
+    ) -> Self {
+        Self {
+            center,
+            ground_center: game_metrics.ground_center,
+            ground_start: screen_metrics.ground_start,
+            ground_size: game_metrics.ground_size,
+            reach,
+            tile_size: screen_metrics.tile_size,
+        }
+    }
+
+    /// Whether the position is past the horizon.
+    pub fn behind(&self, pos: Vec2) -> bool {
+        pos.y < self.horizon()
+    }
+
+    /// World row at the horizon, which is the top row of the ground.
+    pub fn horizon(&self) -> f32 {
+        self.center.y - self.ground_center.y
+    }
+
+    /// Screen point at the bottom left of a tile, where things on it stand,
+    /// with sprites rising from there.
+    pub fn screen(&self, pos: Vec2) -> Vec2 {
+        let offset = pos - self.center + self.ground_center;
+        self.ground_start + vec2(offset.x, offset.y.abs()) * self.tile_size
+    }
+
+    /// Finds the ground tile whose standing sprite covers a screen point, if
+    /// the point is on the ground.
+    pub fn tile_at(&self, point: Vec2) -> Option<IVec2> {
+        let offset = (point - self.ground_start) / self.tile_size;
+        if offset.cmplt(Vec2::ZERO).any() || offset.cmpge(self.ground_size).any() {
+            return None;
+        }
+        // A tile's sprite stands in the cell above its foot, and the foot line
+        // itself belongs to the tile standing on it.
+        let pos = offset + self.center - self.ground_center;
+        Some(ivec2(pos.x.floor() as i32, pos.y.ceil() as i32))
+    }
+
+    /// Range of tiles on the ground in view, from start to just before end.
+    pub fn ground(&self) -> (IVec2, IVec2) {
+        let start = self.center - self.ground_center;
+        // Rows stand a tile up, so the one just past the bottom shows too.
+        let end = start + self.ground_size + vec2(0.0, 1.0);
+        (start.floor().as_ivec2(), end.ceil().as_ivec2())
+    }
+
+    /// Range of tiles that might show some part of a sprite, from start to
+    /// just before end.
+    pub fn visible(&self) -> (IVec2, IVec2) {
+        let start = self.center - self.ground_center;
+        let end = start + self.ground_size;
+        (
+            (start - self.reach).floor().as_ivec2(),
+            (end + self.reach).ceil().as_ivec2(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera(center: Vec2) -> Camera {
+        let game_metrics = GameMetrics::default();
+        let scale = vec2(2.0, 2.0);
+        let full_start = vec2(5.0, 7.0);
+        let tile_size = game_metrics.tile_size_px * scale;
+        let screen_metrics = ScreenMetrics {
+            full_size: game_metrics.full_size_px * scale,
+            full_start,
+            ground_size: game_metrics.ground_size_px * scale,
+            ground_start: full_start + game_metrics.ground_start_px * scale,
+            icon_size: 16.0,
+            scale,
+            sky_size: game_metrics.sky_size_px * scale,
+            sky_start: full_start,
+            tile_size,
+            ui_size: game_metrics.ui_size_px * scale,
+            ui_start: full_start,
+        };
+        Camera::new(center, Vec2::ZERO, &game_metrics, &screen_metrics)
+    }
+
+    #[test]
+    fn player_stands_at_ground_center() {
+        let camera = camera(vec2(100.0, 50.0));
+        // Ground starts 3 sky rows down, and the center is 7 by 4 tiles in.
+        assert_eq!(camera.screen(camera.center), vec2(5.0 + 140.0, 61.0 + 72.0));
+        assert_eq!(camera.horizon(), 46.0);
+    }
+
+    #[test]
+    fn horizon_row_sits_on_ground_top() {
+        let camera = camera(vec2(100.0, 50.0));
+        let pos = vec2(93.0, camera.horizon());
+        assert_eq!(camera.screen(pos), camera.ground_start);
+        assert!(!camera.behind(pos));
+    }
+
+    #[test]
+    fn rows_behind_horizon_fold_down() {
+        let camera = camera(vec2(100.0, 50.0));
+        let behind = vec2(101.0, camera.horizon() - 2.0);
+        let ahead = vec2(101.0, camera.horizon() + 2.0);
+        assert!(camera.behind(behind));
+        assert_eq!(camera.screen(behind), camera.screen(ahead));
+    }
+
+    #[test]
+    fn tile_at_inverts_screen() {
+        let camera = camera(vec2(100.0, 50.0));
+        let (start, end) = camera.ground();
+        // Skip the horizon row, whose sprites stand up in the sky.
+        for y in start.y + 1..end.y {
+            for x in start.x..end.x {
+                let pos = ivec2(x, y);
+                let foot = camera.screen(pos.as_vec2());
+                // The last row's feet fall just off the bottom of the ground.
+                if y < end.y - 1 {
+                    assert_eq!(camera.tile_at(foot), Some(pos), "{pos}");
+                }
+                let body = foot + camera.tile_size * vec2(0.5, -0.5);
+                assert_eq!(camera.tile_at(body), Some(pos), "{pos}");
+            }
+        }
+        assert_eq!(camera.tile_at(camera.ground_start - vec2(1.0, 1.0)), None);
+    }
+}
@@ -0,0 +1,20 @@
+use macroquad::prelude::*;
+
+/// Parses a size like `300x200`, for command line options.
+pub fn parse_size(text: &str) -> Result<IVec2, String> {
+    let size = text
+        .split_once('x')
+        .and_then(|(x, y)| Some(ivec2(x.parse().ok()?, y.parse().ok()?)))
+        .ok_or_else(|| format!("expected a width and height like 300x200, not {text}"))?;
+    match size.min_element() > 0 {
+        true => Ok(size),
+        false => Err(format!("{text} has no area")),
+    }
+}
+
+/// Parses a tile position like `40,25`, for command line options.
+pub fn parse_pos(text: &str) -> Result<IVec2, String> {
+    text.split_once(',')
+        .and_then(|(x, y)| Some(ivec2(x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or_else(|| format!("expected a tile position like 40,25, not {text}"))
+}
@@ -0,0 +1,162 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
+
+use crate::world::AnimalKind;
+
+/// Keeps world time in ticks, from which the time of day follows.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Clock {
+    pub ticks: u64,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Phase {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+/// When a kind is up and about.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Activity {
+    /// Around dawn and dusk.
+    Crepuscular,
+    Diurnal,
+    Nocturnal,
+}
+
+impl Clock {
+    /// Ten minutes at four ticks per second.
+    pub const DAY_TICKS: u64 = 2400;
+
+    /// Starts in the early morning of the first day.
+    pub fn new() -> Self {
+        Self {
+            ticks: Self::DAY_TICKS * 3 / 10,
+        }
+    }
+
+    pub fn advance(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// How dark the sky is, from 0 in daylight to 1 at night.
+    pub fn darkness(&self) -> f32 {
+        1.0 - gradient(&LIGHT_LEVELS, self.time_of_day())
+    }
+
+    pub fn day(&self) -> u64 {
+        self.ticks / Self::DAY_TICKS
+    }
+
+    /// Color to multiply lit things by.
+    pub fn light(&self) -> Color {
+        let darkness = self.darkness();
+        let light = NIGHT_LIGHT.lerp(Vec4::ONE, 1.0 - darkness);
+        // Warm the light near sunrise and sunset.
+        let warmth = 1.0 - (darkness - 0.5).abs() * 2.0;
+        let warm = Vec4::ONE.lerp(SUNSET_LIGHT, warmth);
+        Color::from_vec(light * warm)
+    }
+
+    pub fn phase(&self) -> Phase {
+        match self.time_of_day() {
+            time if time < 0.2 => Phase::Night,
+            time if time < 0.3 => Phase::Dawn,
+            time if time < 0.7 => Phase::Day,
+            time if time < 0.8 => Phase::Dusk,
+            _ => Phase::Night,
+        }
+    }
+
+    pub fn sky(&self) -> Color {
+        let keys = SKY_COLORS.map(|(time, hex)| (time, Color::from_hex(hex).to_vec()));
+        Color::from_vec(gradient(&keys, self.time_of_day()))
+    }
+
+    /// Fraction of the way through the day, starting at midnight.
+    pub fn time_of_day(&self) -> f32 {
+        (self.ticks % Self::DAY_TICKS) as f32 / Self::DAY_TICKS as f32
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Activity {
+    /// Chance of moving about in the given phase.
+    pub fn level(self, phase: Phase) -> f32 {
+        match (self, phase) {
+            (Activity::Crepuscular, Phase::Dawn | Phase::Dusk) => 1.0,
+            (Activity::Diurnal, Phase::Day) => 1.0,
+            (Activity::Nocturnal, Phase::Night) => 1.0,
+            (Activity::Crepuscular, _) | (_, Phase::Dawn | Phase::Dusk) => 0.3,
+            _ => 0.05,
+        }
+    }
+}
+
+impl AnimalKind {
+    pub fn activity(self) -> Activity {
+        match self {
+            AnimalKind::Bead => Activity::Diurnal,
+            AnimalKind::Bob => Activity::Nocturnal,
+            AnimalKind::Coyote => Activity::Nocturnal,
+            AnimalKind::Jack => Activity::Crepuscular,
+            AnimalKind::Javelina => Activity::Crepuscular,
+            AnimalKind::Rattler => Activity::Nocturnal,
+            AnimalKind::Runner => Activity::Diurnal,
+            AnimalKind::Turkey => Activity::Diurnal,
+        }
+    }
+}
+
+/// Multiplies colors channel by channel, keeping the first alpha.
+pub fn shade(color: Color, light: Color) -> Color {
+    Color::new(
+        color.r * light.r,
+        color.g * light.g,
+        color.b * light.b,
+        color.a,
+    )
+}
+
+/// Interpolates a looping list of (time of day, value) keys.
+fn gradient<T>(keys: &[(f32, T)], time: f32) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    let (mut prev, mut prev_value) = keys[keys.len() - 1];
+    prev -= 1.0;
+    for &(next, next_value) in keys.iter().chain([&(keys[0].0 + 1.0, keys[0].1)]) {
+        if time < next {
+            let t = (time - prev) / (next - prev);
+            return prev_value + (next_value - prev_value) * t;
+        }
+        (prev, prev_value) = (next, next_value);
+    }
+    prev_value
+}
+
+/// Light level through the day, with full light from mid morning.
+const LIGHT_LEVELS: [(f32, f32); 4] = [(0.2, 0.0), (0.32, 1.0), (0.68, 1.0), (0.8, 0.0)];
+
+/// Moonlit blue, as a multiplier.
+const NIGHT_LIGHT: Vec4 = Vec4::new(0.3, 0.35, 0.55, 1.0);
+
+/// Sunrise and sunset orange, as a multiplier.
+const SUNSET_LIGHT: Vec4 = Vec4::new(1.0, 0.75, 0.6, 1.0);
+
+const SKY_COLORS: [(f32, u32); 6] = [
+    (0.18, 0x0B1026),
+    (0.26, 0xF2A07B),
+    (0.34, 0xA5C7ED),
+    (0.66, 0xA5C7ED),
+    (0.74, 0xE9795B),
+    (0.82, 0x0B1026),
+];
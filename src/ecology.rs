@@ -0,0 +1,229 @@
+use macroquad::prelude::*;
+
+use crate::info::GameMetrics;
+use crate::sim::DIRECTIONS;
+use crate::world::{Animal, AnimalKind, ChunkGrid, Occupant, World};
+
+/// How a kind lives and dies, with rates as chances per tick.
+#[derive(Copy, Clone, Debug)]
+pub struct Vitals {
+    /// Chance of breeding when the population is well under capacity.
+    pub breed: f32,
+    /// Chance of dying from anything but hunger.
+    pub death: f32,
+    /// Ticks a predator survives without eating.
+    pub starve: u32,
+}
+
+impl AnimalKind {
+    /// What this kind hunts, if anything.
+    pub fn prey(self) -> &'static [AnimalKind] {
+        match self {
+            AnimalKind::Bob | AnimalKind::Coyote => &[AnimalKind::Jack],
+            AnimalKind::Runner => &[AnimalKind::Rattler],
+            _ => &[],
+        }
+    }
+
+    pub fn vitals(self) -> Vitals {
+        let (breed, death, starve) = match self {
+            AnimalKind::Bead => (0.002, 0.0005, 0),
+            AnimalKind::Bob => (0.004, 0.0005, 3000),
+            AnimalKind::Coyote => (0.004, 0.0005, 2500),
+            AnimalKind::Jack => (0.02, 0.002, 0),
+            AnimalKind::Javelina => (0.004, 0.0008, 0),
+            AnimalKind::Rattler => (0.006, 0.001, 0),
+            AnimalKind::Runner => (0.005, 0.001, 2500),
+            AnimalKind::Turkey => (0.005, 0.001, 0),
+        };
+        Vitals {
+            breed,
+            death,
+            starve,
+        }
+    }
+}
+
+impl World {
+    /// Most of each kind that the loaded area supports, indexed by kind.
+    pub fn capacities(&self) -> [usize; AnimalKind::ALL.len()] {
+        // Biomes change slowly, so sampling a few tiles per chunk is enough.
+        const STRIDE: i32 = 8;
+        let metrics = GameMetrics::default();
+        let area_size = metrics.ground_size.x * metrics.ground_size.y;
+        let sample_area = (STRIDE * STRIDE) as f32;
+        // Animals generation is expected to place, by each biome's odds.
+        let mut expected = [0.0; AnimalKind::ALL.len()];
+        for &coord in self.grid.chunks.keys() {
+            let chunk_start = coord * ChunkGrid::CHUNK_SIZE;
+            for x in (0..ChunkGrid::CHUNK_SIZE).step_by(STRIDE as usize) {
+                for y in (0..ChunkGrid::CHUNK_SIZE).step_by(STRIDE as usize) {
+                    let pos = chunk_start + ivec2(x, y);
+                    if !self.contains(pos) {
+                        continue;
+                    }
+                    let odds = self.biome_at(pos).animal_odds();
+                    let total: f32 = odds.iter().map(|(_, chance)| chance).sum();
+                    for &(kind, chance) in odds {
+                        expected[kind as usize] += sample_area / area_size * chance / total;
+                    }
+                }
+            }
+        }
+        // About twice that, with room for the chance spread of small counts.
+        expected.map(|expected: f32| (2.0 * expected + 3.0 * expected.sqrt()).ceil() as usize)
+    }
+
+    pub fn population(&self, kind: AnimalKind) -> usize {
+        self.animals
+            .values()
+            .filter(|animal| animal.kind == kind)
+            .count()
+    }
+
+    /// Runs one tick of hunting, breeding and dying for every animal.
+    pub(crate) fn live(&mut self, player: IVec2) {
+        let capacities = self.capacities();
+        let mut counts = AnimalKind::ALL.map(|kind| self.population(kind));
+        for handle in self.animals.handles() {
+            // Skip any eaten earlier this tick.
+            let Some(animal) = self.animals.get_mut(handle) else {
+                continue;
+            };
+            let kind = animal.kind;
+            let vitals = kind.vitals();
+            let pos = animal.pos.as_ivec2();
+            if !kind.prey().is_empty() {
+                animal.hunger += 1;
+                if animal.hunger > vitals.starve {
+                    self.remove_animal(handle);
+                    counts[kind as usize] -= 1;
+                    continue;
+                }
+                // Eat any prey alongside.
+                for step in DIRECTIONS {
+                    if let Some(Occupant::Animal(prey)) =
+                        self.grid.at(self.wrap(pos + step)).occupant
+                        && let Some(&Animal {
+                            kind: prey_kind, ..
+                        }) = self.animals.get(prey)
+                        && kind.prey().contains(&prey_kind)
+                    {
+                        self.remove_animal(prey);
+                        counts[prey_kind as usize] -= 1;
+                        self.animals[handle].hunger = 0;
+                        break;
+                    }
+                }
+            }
+            if self.rng.gen_range(0.0, 1.0) < vitals.death {
+                self.remove_animal(handle);
+                counts[kind as usize] -= 1;
+                continue;
+            }
+            // Breed less as the population nears capacity, and not when hungry.
+            let capacity = capacities[kind as usize].max(1) as f32;
+            let room = (1.0 - counts[kind as usize] as f32 / capacity).max(0.0);
+            let fed = self.animals[handle].hunger < vitals.starve / 2 || vitals.starve == 0;
+            if fed && self.rng.gen_range(0.0, 1.0) < vitals.breed * room {
+                let step = DIRECTIONS[self.rng.gen_range(0, DIRECTIONS.len())];
+                if self.wrap(pos + step) != player && self.spawn_animal(kind, pos + step).is_some()
+                {
+                    counts[kind as usize] += 1;
+                }
+            }
+        }
+        self.immigrate(&counts, &capacities);
+    }
+
+    /// Brings in animals from beyond the loaded area, so kinds that die out
+    /// nearby can return.
+    fn immigrate(&mut self, counts: &[usize], capacities: &[usize]) {
+        const CHANCE: f32 = 0.002;
+        let Some(min) = self.grid.chunks.keys().copied().reduce(IVec2::min) else {
+            return;
+        };
+        let max = self.grid.chunks.keys().copied().reduce(IVec2::max).unwrap();
+        let start = min * ChunkGrid::CHUNK_SIZE;
+        let end = (max + 1) * ChunkGrid::CHUNK_SIZE - 1;
+        for ((kind, &count), &capacity) in AnimalKind::ALL.iter().zip(counts).zip(capacities) {
+            if (count as f32) < capacity as f32 / 4.0 && self.rng.gen_range(0.0, 1.0) < CHANCE {
+                // Somewhere along the edge of the loaded area.
+                let mut pos = ivec2(
+                    self.rng.gen_range(start.x, end.x + 1),
+                    self.rng.gen_range(start.y, end.y + 1),
+                );
+                match self.rng.gen_range(0, 4) {
+                    0 => pos.x = start.x,
+                    1 => pos.x = end.x,
+                    2 => pos.y = start.y,
+                    _ => pos.y = end.y,
+                }
+                self.spawn_animal(*kind, pos);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A default-sized world with chunks loaded around the player, as a new
+    /// game starts.
+    fn started(seed: u64) -> (World, Vec2) {
+        let mut world = World::generate(seed, Some(World::DEFAULT_SIZE));
+        let player = world.center();
+        world.update_chunks(player);
+        (world, player)
+    }
+
+    fn assert_within_capacity(world: &World, capacities: &[usize], seed: u64, tick: usize) {
+        for kind in AnimalKind::ALL {
+            let population = world.population(kind);
+            let capacity = capacities[kind as usize];
+            assert!(
+                population <= capacity,
+                "{kind:?} at {population} over {capacity} on tick {tick} of seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn generation_stays_within_capacity() {
+        for seed in 0..100 {
+            let (world, _) = started(seed);
+            assert_within_capacity(&world, &world.capacities(), seed, 0);
+        }
+    }
+
+    #[test]
+    fn populations_stay_within_capacity() {
+        for seed in [0, 10, 23] {
+            let (mut world, player) = started(seed);
+            // The player stays put, so the loaded area and its capacities do too.
+            let capacities = world.capacities();
+            for tick in 0..5_000 {
+                world.tick(player);
+                assert_within_capacity(&world, &capacities, seed, tick);
+                if tick % 500 == 0 {
+                    assert_handles_resolve(&world);
+                }
+            }
+            assert_handles_resolve(&world);
+        }
+    }
+
+    /// Checks every animal on the grid is still in the arena.
+    fn assert_handles_resolve(world: &World) {
+        for chunk in world.grid.chunks.values() {
+            for x in 0..chunk.size_x() {
+                for y in 0..chunk.size_y() {
+                    if let Some(Occupant::Animal(handle)) = chunk.at(x, y).occupant {
+                        assert!(world.animals.get(handle).is_some());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::save::SaveError;
+use crate::world::{ChunkGrid, World};
+
+/// Which tiles the player has seen, as a bit per tile in chunk-sized blocks.
+#[derive(Clone, Debug, Default)]
+pub struct Explored {
+    chunks: HashMap<IVec2, [u64; WORDS]>,
+    count: usize,
+}
+
+/// Words of bits per chunk.
+const WORDS: usize = (ChunkGrid::CHUNK_SIZE * ChunkGrid::CHUNK_SIZE / 64) as usize;
+
+impl Explored {
+    pub fn contains(&self, pos: IVec2) -> bool {
+        let (coord, word, bit) = Self::place(pos);
+        self.chunks
+            .get(&coord)
+            .is_some_and(|bits| bits[word] & bit != 0)
+    }
+
+    /// How many tiles have been seen.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn insert(&mut self, pos: IVec2) {
+        let (coord, word, bit) = Self::place(pos);
+        let bits = self.chunks.entry(coord).or_insert([0; WORDS]);
+        if bits[word] & bit == 0 {
+            bits[word] |= bit;
+            self.count += 1;
+        }
+    }
+
+    /// Packs each chunk's bits as hex, in chunk order, for saving.
+    pub fn pack(&self) -> Vec<(IVec2, String)> {
+        let mut packed: Vec<(IVec2, String)> = self
+            .chunks
+            .iter()
+            .map(|(&coord, bits)| {
+                let hex = bits.iter().map(|word| format!("{word:016x}")).collect();
+                (coord, hex)
+            })
+            .collect();
+        packed.sort_by_key(|(coord, _)| (coord.x, coord.y));
+        packed
+    }
+
+    /// Reverses [`Explored::pack`], refusing any chunk that doesn't parse.
+    pub fn unpack(packed: &[(IVec2, String)]) -> Result<Self, SaveError> {
+        let mut explored = Self::default();
+        for (coord, hex) in packed {
+            let words: Option<Vec<u64>> = (0..WORDS)
+                .map(|index| {
+                    let word = hex.get(index * 16..(index + 1) * 16)?;
+                    u64::from_str_radix(word, 16).ok()
+                })
+                .collect();
+            let Some(Ok(bits)) = words.map(<[u64; WORDS]>::try_from) else {
+                return Err(SaveError::Explored(*coord));
+            };
+            if hex.len() != WORDS * 16 || explored.chunks.contains_key(coord) {
+                return Err(SaveError::Explored(*coord));
+            }
+            explored.count += bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
+            explored.chunks.insert(*coord, bits);
+        }
+        Ok(explored)
+    }
+
+    fn place(pos: IVec2) -> (IVec2, usize, u64) {
+        let local = pos.rem_euclid(IVec2::splat(ChunkGrid::CHUNK_SIZE));
+        let index = (local.y * ChunkGrid::CHUNK_SIZE + local.x) as usize;
+        (ChunkGrid::chunk_coord(pos), index / 64, 1 << (index % 64))
+    }
+}
+
+impl World {
+    /// Marks tiles from start to just before end as seen.
+    pub fn explore(&mut self, start: IVec2, end: IVec2) {
+        for y in start.y..end.y {
+            for x in start.x..end.x {
+                let pos = self.wrap(ivec2(x, y));
+                if self.contains(pos) {
+                    self.explored.insert(pos);
+                }
+            }
+        }
+    }
+
+    /// Share of the world seen so far, if it has bounds.
+    pub fn explored_share(&self) -> Option<f32> {
+        let size = self.size?;
+        Some(self.explored.count() as f32 / (size.x as f32 * size.y as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_round_trips() {
+        let mut explored = Explored::default();
+        for pos in [
+            ivec2(0, 0),
+            ivec2(31, 31),
+            ivec2(32, 5),
+            ivec2(-1, -40),
+            ivec2(700, 3),
+        ] {
+            explored.insert(pos);
+        }
+        // Seeing a tile twice doesn't count it twice.
+        explored.insert(ivec2(0, 0));
+        let packed = explored.pack();
+        let unpacked = Explored::unpack(&packed).unwrap();
+        assert_eq!(unpacked.count(), 5);
+        assert_eq!(unpacked.pack(), packed);
+        assert!(unpacked.contains(ivec2(-1, -40)));
+        assert!(!unpacked.contains(ivec2(1, 0)));
+    }
+
+    #[test]
+    fn unpack_refuses_bad_chunks() {
+        let mut explored = Explored::default();
+        explored.insert(ivec2(3, 4));
+        let good = explored.pack();
+        let hex = &good[0].1;
+        let bad = [
+            hex[1..].to_string(),
+            format!("{hex}0"),
+            hex.replacen('0', "g", 1),
+        ];
+        for bad in bad {
+            let packed = [good[0].clone(), (ivec2(1, 2), bad)];
+            assert!(matches!(
+                Explored::unpack(&packed),
+                Err(SaveError::Explored(coord)) if coord == ivec2(1, 2)
+            ));
+        }
+        let doubled = [good[0].clone(), good[0].clone()];
+        assert!(Explored::unpack(&doubled).is_err());
+    }
+}
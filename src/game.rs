@@ -1,26 +1,72 @@
-use macroquad::ui::{Skin, hash, root_ui, widgets};
+use macroquad::ui::{Skin, root_ui};
 use macroquad::{miniquad::window::screen_size, prelude::*};
+use std::f32::consts::PI;
 
-use crate::assets::Assets;
-use crate::info::{GameMetrics, ScreenMetrics};
-use crate::world::{AnimalKind, Occupant, Plant, Tile, World};
+use greeny::camera::Camera;
+use greeny::clock::shade;
+use greeny::info::{GameMetrics, ScreenMetrics};
+use greeny::noise::hash2;
+use greeny::save::{PlayerSave, SAVE_VERSION, SaveData, SaveError};
+use greeny::settings::Settings;
+use greeny::weather::WeatherKind;
+use greeny::world::{Edge, Occupant, Plant, Tile, World};
+
+use crate::args::Args;
+use crate::assets::{AssetError, Assets};
+use crate::menu::Field;
+use crate::parallax::{Layer, ridge_height};
+use crate::view::MapView;
 
-#[derive(Default)]
 pub struct Game {
+    /// Directions currently held down.
+    held: Input,
+    /// Seconds the current direction has been held.
+    hold_time: f32,
+    /// Taps not yet acted on.
     input: Input,
     assets: Option<Assets>,
-    fullscreen: bool,
+    /// Where a drag on the map was last, and whether it's moved at all.
+    drag: Option<(Vec2, bool)>,
+    /// Where the player is drawn, trailing `pos` while walking between tiles.
+    draw_pos: Vec2,
     facing_x: f32,
+    /// Whether the window is fullscreen, which the command line can turn on
+    /// for one run without changing the setting.
+    fullscreen: bool,
     game_metrics: GameMetrics,
+    /// Tile clicked to walk to, if any.
+    goal: Option<IVec2>,
     icon_skin: Option<Skin>,
+    /// The full screen world map.
+    map: MapView,
+    map_open: bool,
+    minimap: MapView,
     mouse: Vec2,
+    /// Hold time at which the next repeated step can go.
+    next_repeat: f32,
+    /// Steps still to take toward the goal, in order.
+    path: Vec<IVec2>,
     pos: Vec2,
+    /// Whether saving may write the save file, which one-off worlds leave
+    /// alone.
+    saving: bool,
     screen_metrics: ScreenMetrics,
+    /// Row picked on the settings screen.
+    setting: usize,
+    settings: Settings,
+    settings_open: bool,
+    /// Time not yet simulated, in seconds.
+    tick_time: f32,
     ui_hidden: bool,
+    /// Distance walked in tiles, for animation.
+    walked: f32,
     world: World,
 }
 
-#[derive(Default)]
+/// Minimap size in map pixels, which are also game pixels.
+const MINIMAP_SIZE: IVec2 = IVec2::new(30, 20);
+
+#[derive(Clone, Copy, Default)]
 pub struct Input {
     down: bool,
     left: bool,
@@ -28,31 +74,137 @@ pub struct Input {
     up: bool,
 }
 
+impl Input {
+    /// Combines held directions into one step, which might be diagonal.
+    fn direction(self) -> Vec2 {
+        let axis = |neg: bool, pos: bool| pos as i32 as f32 - neg as i32 as f32;
+        vec2(axis(self.left, self.right), axis(self.up, self.down))
+    }
+}
+
 impl Game {
-    pub async fn run(&mut self) {
-        self.load();
+    pub fn new(world: World, settings: Settings) -> Self {
+        let game_metrics = GameMetrics::default();
+        Self {
+            held: Default::default(),
+            hold_time: 0.0,
+            input: Default::default(),
+            assets: None,
+            drag: None,
+            draw_pos: world.center(),
+            facing_x: 1.0,
+            fullscreen: false,
+            game_metrics,
+            goal: None,
+            icon_skin: None,
+            map: MapView::new(game_metrics.full_size_px.as_ivec2(), 4.0),
+            map_open: false,
+            minimap: MapView::new(MINIMAP_SIZE, 2.0),
+            mouse: Default::default(),
+            next_repeat: 0.0,
+            path: vec![],
+            pos: world.center(),
+            saving: true,
+            screen_metrics: Default::default(),
+            setting: 0,
+            settings,
+            settings_open: false,
+            tick_time: 0.0,
+            ui_hidden: false,
+            walked: 0.0,
+            world,
+        }
+    }
+
+    /// Resumes the player and world from a save.
+    pub fn from_save(save: SaveData, settings: Settings) -> Result<Self, SaveError> {
+        let mut game = Self::new(World::from_save(save.world)?, settings);
+        game.facing_x = save.player.facing_x;
+        game.pos = save.player.pos;
+        game.draw_pos = save.player.pos;
+        Ok(game)
+    }
+
+    /// Moves the player to a tile, if it's in the world.
+    pub fn place_player(&mut self, pos: IVec2) -> bool {
+        let pos = self.world.wrap(pos);
+        if !self.world.contains(pos) {
+            return false;
+        }
+        self.pos = pos.as_vec2();
+        self.draw_pos = self.pos;
+        self.goal = None;
+        self.path.clear();
+        true
+    }
+
+    /// Keeps from writing over the save, such as for a world started from
+    /// command line options while another is saved.
+    pub fn keep_save(&mut self) {
+        self.saving = false;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.world.seed
+    }
+
+    pub fn save_data(&self) -> SaveData {
+        SaveData {
+            version: SAVE_VERSION,
+            player: PlayerSave {
+                facing_x: self.facing_x,
+                pos: self.pos,
+            },
+            world: self.world.save(),
+        }
+    }
+
+    pub async fn run(&mut self, args: &Args) -> Result<(), AssetError> {
+        self.load(args)?;
+        // Hold off closing until we've saved.
+        prevent_quit();
         // set_camera(&Camera2D::from_display_rect(Rect::new(0.0, 0.0, 500.0, 500.0)));
         loop {
+            if is_quit_requested() {
+                self.save();
+                break;
+            }
+            if let Some(assets) = &mut self.assets
+                && assets.reload_changed(&self.game_metrics)
+            {
+                // The skin holds its own copy of the icon font.
+                self.update_skin();
+            }
+            self.update_world();
             self.update_screen();
-            if !self.ui_hidden {
+            if !self.ui_hidden && !self.map_open && !self.settings_open {
                 self.ui();
             }
             self.handle_input();
+            self.update_motion();
+            self.update_maps();
             self.draw();
             next_frame().await
         }
+        Ok(())
     }
 
-    fn draw(&self) {
-        let Self {
-            assets: Some(assets),
-            game_metrics,
-            screen_metrics,
-            ..
-        } = self
-        else {
-            panic!()
+    /// Views the world around the player, as drawn.
+    fn camera(&self) -> Camera {
+        let reach = match &self.assets {
+            Some(assets) => assets.tile_info.largest / self.game_metrics.tile_size_px,
+            None => Vec2::ZERO,
         };
+        Camera::new(
+            self.draw_pos,
+            reach,
+            &self.game_metrics,
+            &self.screen_metrics,
+        )
+    }
+
+    fn draw(&self) {
+        let Self { screen_metrics, .. } = self;
         clear_background(BLACK);
         // Clip so we can be sloppy in drawing.
         let gl = unsafe { get_internal_gl().quad_gl };
@@ -62,69 +214,371 @@ impl Game {
             screen_metrics.full_size.x as i32,
             screen_metrics.full_size.y as i32,
         )));
+        let clock = &self.world.clock;
         // Sky.
         draw_rectangle(
             screen_metrics.sky_start.x,
             screen_metrics.sky_start.y,
             screen_metrics.sky_size.x,
             screen_metrics.sky_size.y,
-            Color::from_hex(0xA5C7ED),
+            clock.sky(),
         );
-        // Mountains.
-        draw_texture_ex(
-            &assets.mountains,
-            screen_metrics.full_start.x,
-            screen_metrics.full_start.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(screen_metrics.full_size - vec2(0.0, game_metrics.tile_size_px.y)),
-                source: Some(Rect::new(
-                    0.0,
-                    game_metrics.tile_size_px.y,
-                    game_metrics.full_size_px.x,
-                    game_metrics.full_size_px.y - game_metrics.tile_size_px.y,
-                )),
-                ..Default::default()
-            },
-        );
-        // Behind horizon.
-        self.draw_world(false);
-        // Ground.
+        self.draw_night_sky();
+        self.draw_backdrop();
+        self.draw_world(&self.camera());
+        self.draw_weather();
+        match self.map_open {
+            true => self.draw_map(&self.map, self.map_rect()),
+            false => self.draw_map(&self.minimap, self.minimap_rect()),
+        }
+        if self.settings_open {
+            self.draw_settings();
+        }
+        // Reset clip rect to draw ui.
+        gl.scissor(None);
+        if !self.ui_hidden {
+            self.draw_status();
+        }
+        root_ui().pop_skin();
+    }
+
+    /// Draws mountains, ridges and far plants, each scrolling at its own rate
+    /// as the player walks east and west.
+    fn draw_backdrop(&self) {
+        let Self {
+            assets: Some(assets),
+            game_metrics,
+            screen_metrics,
+            world,
+            ..
+        } = self
+        else {
+            panic!()
+        };
+        let light = world.clock.light();
+        let sky = world.clock.sky();
+        // Farther things fade toward the sky.
+        let haze = |color: Color, amount: f32| {
+            let color = shade(color, light).to_vec().lerp(sky.to_vec(), amount);
+            Color::from_vec(color.with_w(1.0))
+        };
+        let ground = self.draw_pos.x * game_metrics.tile_size_px.x;
+        let wrap = match (world.edge, world.size) {
+            (Edge::Wrap, Some(size)) => Some(size.x as f32 * game_metrics.tile_size_px.x),
+            _ => None,
+        };
+        let width = game_metrics.full_size_px.x;
+        let scale = screen_metrics.scale;
+        let start = screen_metrics.full_start;
+        let horizon = screen_metrics.ground_start.y;
+        // Mountains, alternately flipped so each copy meets the next.
+        let scroll = Layer::MOUNTAINS.scroll(ground, wrap);
+        let copy_width = Layer::MOUNTAINS.period * 0.5;
+        for copy in 0..=((width + scroll) / copy_width) as i32 {
+            let x = start.x + (copy as f32 * copy_width - scroll) * scale.x;
+            draw_texture_ex(
+                &assets.mountains,
+                x.floor(),
+                start.y,
+                light,
+                DrawTextureParams {
+                    dest_size: Some(
+                        vec2(
+                            copy_width,
+                            game_metrics.full_size_px.y - game_metrics.tile_size_px.y,
+                        ) * scale,
+                    ),
+                    flip_x: copy % 2 == 1,
+                    source: Some(Rect::new(
+                        0.0,
+                        game_metrics.tile_size_px.y,
+                        copy_width,
+                        game_metrics.full_size_px.y - game_metrics.tile_size_px.y,
+                    )),
+                    ..Default::default()
+                },
+            );
+        }
+        // Ridges, a pixel column at a time.
+        let scroll = Layer::RIDGES.scroll(ground, wrap);
+        let color = haze(Color::from_hex(0x9C7E66), 0.35);
+        for column in 0..width as i32 {
+            let at = (column as f32 + scroll).rem_euclid(Layer::RIDGES.period);
+            let height = ridge_height(world.seed, at).round() * scale.y;
+            let x = start.x + column as f32 * scale.x;
+            draw_rectangle(x, horizon - height, scale.x, height, color);
+        }
+        // Plants, small and hazy, at hashed places in slots along the layer.
+        const SLOT: f32 = 40.0;
+        let layer = Layer::PLANTS;
+        let scroll = layer.scroll(ground, wrap);
+        let color = haze(Color::new(0.45, 0.55, 0.4, 1.0), 0.5);
+        let slots = (layer.period / SLOT) as i32;
+        for copy in 0..=((width + scroll) / layer.period) as i32 {
+            for slot in 0..slots {
+                let bits = hash2(world.seed, slot, -3);
+                let sprite = match bits % 4 {
+                    0 | 1 => &assets.tile_info.saguaro,
+                    2 => &assets.tile_info.ocotillo,
+                    _ => continue,
+                };
+                let source = sprite.rect();
+                let size = (source.size() / 3.0).floor();
+                let offset = ((bits >> 8) % (SLOT - size.x) as u64) as f32;
+                let x = copy as f32 * layer.period + slot as f32 * SLOT + offset - scroll;
+                let pos = vec2(start.x + x * scale.x, horizon - size.y * scale.y);
+                draw_texture_ex(
+                    &assets.tiles,
+                    pos.x.floor(),
+                    pos.y,
+                    color,
+                    DrawTextureParams {
+                        dest_size: Some(size * scale),
+                        source: Some(source),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+
+    fn draw_ground(&self, camera: &Camera) {
+        let Self { screen_metrics, .. } = self;
         draw_rectangle(
             screen_metrics.ground_start.x,
             screen_metrics.ground_start.y,
             screen_metrics.ground_size.x,
             screen_metrics.ground_size.y,
-            Color::from_hex(0xC5AD95),
+            shade(Color::from_hex(0xC5AD95), self.world.clock.light()),
         );
-        let pos = screen_metrics.tile(vec2(7.0, 4.0));
+        self.draw_path(camera);
+    }
+
+    /// Draws a map view into a rect on screen, marking the player and any
+    /// animals in view.
+    fn draw_map(&self, view: &MapView, rect: Rect) {
+        let Some(texture) = view.texture() else {
+            return;
+        };
+        let Self {
+            screen_metrics,
+            world,
+            ..
+        } = self;
+        let border = screen_metrics.scale.x;
+        draw_rectangle(
+            rect.x - border,
+            rect.y - border,
+            rect.w + 2.0 * border,
+            rect.h + 2.0 * border,
+            Color::from_hex(0x3B2F2A),
+        );
+        draw_texture_ex(
+            texture,
+            rect.x,
+            rect.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(rect.size()),
+                ..Default::default()
+            },
+        );
+        let pixel = rect.size() / view.size.as_vec2();
+        let mark = |pos: Vec2, size: Vec2, color: Color| {
+            let at = rect.point() + view.place(world, pos) * pixel - size * 0.5;
+            if rect.contains(at) {
+                draw_rectangle(at.x, at.y, size.x, size.y, color);
+            }
+        };
+        if self.settings.sightings {
+            // Only show animals in view, so the map doesn't spoil the fog.
+            let camera = self.camera();
+            let (start, end) = camera.ground();
+            for animal in world.animals.values() {
+                let pos = camera.center + world.offset(camera.center, animal.pos);
+                let tile = pos.floor().as_ivec2();
+                if tile.cmplt(start).any() || tile.cmpge(end).any() {
+                    continue;
+                }
+                mark(
+                    animal.pos,
+                    pixel.max(screen_metrics.scale),
+                    animal.kind.color(),
+                );
+            }
+        }
+        // Blink the player so it's easy to find.
+        let color = match (get_time() * 2.0) as i64 % 2 {
+            0 => WHITE,
+            _ => RED,
+        };
+        mark(self.draw_pos, 2.0 * screen_metrics.scale, color);
+        if self.map_open {
+            let text = match world.explored_share() {
+                Some(share) => format!("Explored {:.1}%", share * 100.0),
+                None => format!("Explored {} tiles", world.explored.count()),
+            };
+            let font_size = 6.0 * screen_metrics.scale.y;
+            draw_text(
+                &text,
+                rect.x + font_size * 0.5,
+                rect.bottom() - font_size * 0.5,
+                font_size,
+                WHITE,
+            );
+        }
+    }
+
+    /// Draws stars and the moon, fading in as the sky darkens.
+    fn draw_night_sky(&self) {
+        let Self {
+            screen_metrics,
+            world,
+            ..
+        } = self;
+        let darkness = world.clock.darkness();
+        if darkness <= 0.0 {
+            return;
+        }
+        let start = screen_metrics.sky_start;
+        let size = screen_metrics.sky_size;
+        let star_size = screen_metrics.scale;
+        for star in 0..60 {
+            // Fixed places for the world, so stars hold still.
+            let bits = hash2(world.seed, star, 0);
+            let at = vec2((bits & 0xFFFF) as f32, (bits >> 16 & 0xFFFF) as f32) / 65536.0;
+            let twinkle = (bits >> 32 & 0xFF) as f32 / 255.0;
+            let color = Color::new(1.0, 1.0, 0.9, darkness * (0.4 + 0.6 * twinkle));
+            let pos = (start + at * size).floor();
+            draw_rectangle(pos.x, pos.y, star_size.x, star_size.y, color);
+        }
+        // The moon crosses the sky from dusk to dawn.
+        let night = (world.clock.time_of_day() + 0.25).fract() * 2.0;
+        if night < 1.0 {
+            let pos = start + vec2(night * size.x, size.y * (0.7 - 0.5 * (night * PI).sin()));
+            let color = Color::new(0.95, 0.93, 0.8, darkness);
+            draw_circle(pos.x, pos.y, 4.0 * star_size.y, color);
+        }
+    }
+
+    /// Marks the planned walk with a dot on each tile.
+    fn draw_path(&self, camera: &Camera) {
+        let Self { screen_metrics, .. } = self;
+        let dot = 2.0 * screen_metrics.scale;
+        let color = Color::new(1.0, 1.0, 1.0, 0.6);
+        let mut pos = self.pos;
+        for step in &self.path {
+            pos += step.as_vec2();
+            let pos = self.draw_pos + self.world.offset(self.draw_pos, pos);
+            if camera.behind(pos) {
+                continue;
+            }
+            let at = camera.screen(pos) + screen_metrics.tile_size * vec2(0.5, -0.2) - dot * 0.5;
+            draw_rectangle(at.x, at.y, dot.x, dot.y, color);
+        }
+    }
+
+    /// Draws the player, hopping a pixel mid-step while walking.
+    fn draw_player(&self, camera: &Camera) {
+        let Self {
+            assets: Some(assets),
+            screen_metrics,
+            ..
+        } = self
+        else {
+            panic!()
+        };
+        let runner = &assets.tile_info.runner;
+        // Stand still on the first frame, and stride through the rest.
+        let (frame, hop) = match self.draw_pos == self.pos {
+            true => (runner.rect(), 0.0),
+            false => (
+                runner.frames[(self.walked * 2.0) as usize % runner.frames.len()],
+                (self.walked * PI).sin().abs().round(),
+            ),
+        };
+        let pos = camera.screen(self.draw_pos)
+            - vec2(0.0, screen_metrics.tile_size.y)
+            - vec2(0.0, hop) * screen_metrics.scale;
         draw_texture_ex(
             &assets.tiles,
             pos.x,
             pos.y,
-            WHITE,
+            self.world.clock.light(),
             DrawTextureParams {
                 dest_size: Some(screen_metrics.tile_size),
                 flip_x: self.facing_x < 0.0,
-                source: Some(Rect::new(
-                    assets.tile_info.runner.x,
-                    assets.tile_info.runner.y,
-                    game_metrics.tile_size_px.x,
-                    game_metrics.tile_size_px.y,
-                )),
+                source: Some(frame),
                 ..Default::default()
             },
         );
-        self.draw_world(true);
-        // Reset clip rect to draw ui.
-        gl.scissor(None);
-        root_ui().pop_skin();
     }
 
-    fn draw_tile(&self, tile: Tile, pos: Vec2) {
+    /// Lists the settings over a dimmed scene, with the picked one marked.
+    fn draw_settings(&self) {
+        let Self {
+            screen_metrics,
+            settings,
+            ..
+        } = self;
+        let full = self.map_rect();
+        draw_rectangle(
+            full.x,
+            full.y,
+            full.w,
+            full.h,
+            Color::new(0.0, 0.0, 0.0, 0.7),
+        );
+        let font_size = 6.0 * screen_metrics.scale.y;
+        let rows = self.setting_rects();
+        draw_text(
+            "Settings",
+            rows[0].x,
+            rows[0].y - font_size,
+            font_size,
+            WHITE,
+        );
+        for (index, (field, row)) in Field::ALL.iter().zip(rows).enumerate() {
+            if index == self.setting {
+                draw_rectangle(row.x, row.y, row.w, row.h, Color::new(1.0, 1.0, 1.0, 0.2));
+            }
+            let baseline = row.bottom() - (row.h - font_size * 0.6) * 0.5;
+            let pad = font_size * 0.5;
+            draw_text(field.label(), row.x + pad, baseline, font_size, WHITE);
+            let value = format!("< {} >", field.value(settings));
+            let width = measure_text(&value, None, font_size as u16, 1.0).width;
+            draw_text(
+                &value,
+                row.right() - pad - width,
+                baseline,
+                font_size,
+                WHITE,
+            );
+        }
+    }
+
+    fn draw_status(&self) {
+        let Self { screen_metrics, .. } = self;
+        let font_size = 6.0 * screen_metrics.scale.y;
+        let text = format!(
+            "Seed {} Day {}",
+            self.world.seed,
+            self.world.clock.day() + 1
+        );
+        let pos = screen_metrics.ui_start + vec2(0.0, screen_metrics.ui_size.y);
+        draw_text(
+            &text,
+            pos.x + font_size * 0.5,
+            pos.y - font_size * 0.5,
+            font_size,
+            WHITE,
+        );
+    }
+
+    /// Draws what's on a tile, standing at a screen point.
+    fn draw_tile(&self, tile: Tile, foot: Vec2) {
         let Self {
             assets: Some(assets),
-            game_metrics,
             screen_metrics,
             ..
         } = self
@@ -132,43 +586,31 @@ impl Game {
             panic!()
         };
         let Assets { tile_info, .. } = assets;
-        let source = match tile.occupant {
-            Some(occupant) => match occupant {
-                Occupant::Animal(animal_idx) => {
-                    let animal = self.world.animals[animal_idx];
-                    let pos = match animal.kind {
-                        AnimalKind::Bead => tile_info.bead,
-                        AnimalKind::Bob => tile_info.bob,
-                        AnimalKind::Coyote => tile_info.coyote,
-                        AnimalKind::Jack => tile_info.jack,
-                        AnimalKind::Javelina => tile_info.javelina,
-                        AnimalKind::Rattler => tile_info.rattler,
-                        AnimalKind::Runner => tile_info.runner,
-                        AnimalKind::Turkey => tile_info.turkey,
-                    };
-                    let size = game_metrics.tile_size_px;
-                    Rect::new(pos.x, pos.y, size.x, size.y)
-                }
-                Occupant::Plant(plant) => match plant {
-                    Plant::NopalBig => tile_info.nopal_big,
-                    Plant::NopalSmall => tile_info.nopal_small,
-                    Plant::Ocotillo => tile_info.ocotillo,
-                    Plant::Saguaro => tile_info.saguaro,
-                },
-            },
+        let sprite = match tile.occupant {
+            Some(Occupant::Animal(handle)) => {
+                let Some(animal) = self.world.animals.get(handle) else {
+                    return;
+                };
+                tile_info.animal(animal.kind)
+            }
+            Some(Occupant::Plant(plant)) => tile_info.plant(plant),
             None => return,
         };
-        let pos = screen_metrics.tile(pos)
-            - Vec2::floor(
-                (source.size() - game_metrics.tile_size_px) * vec2(0.5, 1.0)
-                    / game_metrics.tile_size_px,
-            ) * game_metrics.tile_size_px
-                * screen_metrics.scale;
+        let source = sprite.rect();
+        let pos =
+            foot - vec2(0.0, screen_metrics.tile_size.y) - sprite.anchor * screen_metrics.scale;
+        let mut light = self.world.clock.light();
+        if tile.occupant == Some(Occupant::Plant(Plant::Ocotillo))
+            && self.world.weather.leafed_out()
+        {
+            // Green up with fresh leaves after rain.
+            light = shade(light, Color::new(0.7, 1.0, 0.65, 1.0));
+        }
         draw_texture_ex(
             &assets.tiles,
             pos.x,
             pos.y,
-            WHITE,
+            light,
             DrawTextureParams {
                 dest_size: Some(screen_metrics.scale * source.size()),
                 source: Some(source),
@@ -177,39 +619,92 @@ impl Game {
         );
     }
 
-    fn draw_world(&self, front: bool) {
+    /// Draws rain, dust and lightning over the scene, along with a veil that
+    /// cuts visibility.
+    fn draw_weather(&self) {
         let Self {
-            game_metrics,
+            screen_metrics,
             world,
             ..
         } = self;
-        let min = Vec2::default();
-        let max = world.grid.size();
-        // Margin needs to be larger than any game item.
-        let margin = vec2(10.0, 10.0);
-        let extent = game_metrics.ground_center + margin;
-        let start = Vec2::clamp(self.pos - extent, min, max);
-        let end = Vec2::clamp(self.pos + extent, min, max);
-        for y in start.y as usize..end.y as usize {
-            let draw_y = match front {
-                false => {
-                    if (y as f32) >= self.pos.y - game_metrics.ground_center.y {
-                        continue;
-                    }
-                    // TODO This formula ended up being hacky.
-                    self.pos.y - (y as f32 - self.pos.y) - margin.y + 2.0
+        let weather = &world.weather;
+        let intensity = weather.intensity();
+        if intensity <= 0.0 {
+            return;
+        }
+        let start = screen_metrics.full_start;
+        let size = screen_metrics.full_size;
+        let scale = screen_metrics.scale;
+        let veil = weather.veil();
+        draw_rectangle(start.x, start.y, size.x, size.y, veil);
+        let time = get_time() as f32;
+        let count = (200.0 * intensity) as i32;
+        for particle in 0..count {
+            // Particles follow fixed paths, so they need no stored state.
+            let bits = hash2(world.seed, particle, weather.kind as i32);
+            let base = vec2((bits & 0xFFFF) as f32, (bits >> 16 & 0xFFFF) as f32) / 65536.0;
+            let speed = 0.8 + (bits >> 32 & 0xFF) as f32 / 255.0;
+            match weather.kind {
+                WeatherKind::Clear => {}
+                WeatherKind::Haboob => {
+                    let wobble = vec2(0.0, 0.02 * (time * 3.0 + base.x * 20.0).sin());
+                    let at = (base + vec2(0.5, 0.05) * speed * time + wobble).rem_euclid(Vec2::ONE);
+                    let pos = start + at * size;
+                    let color = Color::new(0.55, 0.4, 0.25, 0.8);
+                    draw_rectangle(pos.x, pos.y, scale.x, scale.y, color);
                 }
-                true => {
-                    if (y as f32) < self.pos.y - game_metrics.ground_center.y {
-                        continue;
-                    }
-                    y as f32
+                WeatherKind::Monsoon => {
+                    let at = (base + vec2(-0.3, 1.5) * speed * time).rem_euclid(Vec2::ONE);
+                    let pos = start + at * size;
+                    let end = pos + vec2(-1.0, 5.0) * scale;
+                    let color = Color::new(0.75, 0.8, 0.9, 0.6);
+                    draw_line(pos.x, pos.y, end.x, end.y, 0.5 * scale.x, color);
+                }
+            }
+        }
+        // Lightning flashes now and then at the height of a thunderstorm.
+        let tick = world.clock.ticks as i32;
+        if weather.kind == WeatherKind::Monsoon
+            && intensity > 0.5
+            && hash2(world.seed, tick, -1).is_multiple_of(120)
+        {
+            draw_rectangle(
+                start.x,
+                start.y,
+                size.x,
+                size.y,
+                Color::new(1.0, 1.0, 1.0, 0.6),
+            );
+        }
+    }
+
+    /// Draws tiles and the player from back to front, laying the ground over
+    /// the bases of things past the horizon.
+    fn draw_world(&self, camera: &Camera) {
+        let (start, end) = camera.visible();
+        // The player goes in as no tile.
+        let mut drawn = vec![];
+        for y in start.y..end.y {
+            for x in start.x..end.x {
+                if let Some(tile) = self.world.tile(ivec2(x, y))
+                    && tile.occupant.is_some()
+                {
+                    drawn.push((vec2(x as f32, y as f32), Some(tile)));
                 }
-            };
-            for x in start.x as usize..end.x as usize {
-                let tile = world.grid.at(x, y);
-                let pos = vec2(x as f32, draw_y) - start - margin;
-                self.draw_tile(tile, pos);
+            }
+        }
+        drawn.push((self.draw_pos, None));
+        // Stable, so the player stays in front of others in the same row.
+        drawn.sort_by(|(a, _), (b, _)| a.y.total_cmp(&b.y));
+        let mut ground = false;
+        for (pos, tile) in drawn {
+            if !ground && !camera.behind(pos) {
+                self.draw_ground(camera);
+                ground = true;
+            }
+            match tile {
+                Some(tile) => self.draw_tile(tile, camera.screen(pos)),
+                None => self.draw_player(camera),
             }
         }
     }
@@ -232,42 +727,293 @@ impl Game {
                 && is_key_pressed(KeyCode::Enter)
         {
             self.fullscreen = !self.fullscreen;
+            self.settings.fullscreen = self.fullscreen;
             set_fullscreen(self.fullscreen);
+            self.save_settings();
+        }
+        // Quick save and load.
+        if is_key_pressed(KeyCode::F5) {
+            self.save();
+        }
+        if is_key_pressed(KeyCode::F9) {
+            self.restore();
         }
-        // Move.
-        self.input.up |= is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W);
-        self.input.down |= is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S);
-        self.input.left |= is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A);
-        self.input.right |= is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D);
-        if self.input.up {
-            self.maybe_move_by(vec2(0.0, -1.0));
+        // Settings, from escape when the map's closed or a tap on the cog.
+        let tapped_cog = is_mouse_button_pressed(MouseButton::Left)
+            && !self.map_open
+            && self.cog_rect().contains(mouse);
+        if !self.map_open && is_key_pressed(KeyCode::Escape) || tapped_cog {
+            self.settings_open = !self.settings_open;
+            self.input = Default::default();
+            return;
         }
-        if self.input.down {
-            self.maybe_move_by(vec2(0.0, 1.0));
+        if self.settings_open {
+            self.handle_settings_input(mouse);
+            self.held = Default::default();
+            self.input = Default::default();
+            return;
         }
-        if self.input.left {
-            self.maybe_move_by(vec2(-1.0, 0.0));
+        // World map, from its key or a tap on the minimap.
+        let tapped_minimap =
+            is_mouse_button_pressed(MouseButton::Left) && self.minimap_rect().contains(mouse);
+        if is_key_pressed(KeyCode::M)
+            || self.map_open && is_key_pressed(KeyCode::Escape)
+            || !self.map_open && tapped_minimap
+        {
+            self.toggle_map();
+            self.input = Default::default();
+            return;
+        }
+        if self.map_open {
+            self.handle_map_input(mouse);
+            self.held = Default::default();
+            self.input = Default::default();
+            return;
+        }
+        // Move, holding on to taps until the last step finishes.
+        let pressed = |a, b| is_key_pressed(a) || is_key_pressed(b);
+        let down = |a, b| is_key_down(a) || is_key_down(b);
+        self.input.up |= pressed(KeyCode::Up, KeyCode::W);
+        self.input.down |= pressed(KeyCode::Down, KeyCode::S);
+        self.input.left |= pressed(KeyCode::Left, KeyCode::A);
+        self.input.right |= pressed(KeyCode::Right, KeyCode::D);
+        self.held.up |= down(KeyCode::Up, KeyCode::W);
+        self.held.down |= down(KeyCode::Down, KeyCode::S);
+        self.held.left |= down(KeyCode::Left, KeyCode::A);
+        self.held.right |= down(KeyCode::Right, KeyCode::D);
+        // Click or tap the ground to walk there. Taps come through as mouse
+        // presses.
+        if is_mouse_button_pressed(MouseButton::Left)
+            && let Some(goal) = self.camera().tile_at(mouse)
+        {
+            self.goal = Some(self.world.wrap(goal));
+            self.plan_path();
         }
-        if self.input.right {
-            self.maybe_move_by(vec2(1.0, 0.0));
+        let held = self.held.direction();
+        // Buttons report holds fresh each frame.
+        self.held = Default::default();
+        if held == Vec2::ZERO {
+            self.hold_time = 0.0;
+            self.next_repeat = self.settings.repeat_delay;
+        } else {
+            self.hold_time += get_frame_time();
         }
-        // Reset input for next frame.
+        if self.draw_pos != self.pos {
+            return;
+        }
+        let tapped = self.input.direction();
+        let step = if tapped != Vec2::ZERO {
+            tapped
+        } else if held != Vec2::ZERO && self.hold_time >= self.next_repeat {
+            self.next_repeat = self.hold_time + 1.0 / self.settings.repeat_rate;
+            held
+        } else {
+            Vec2::ZERO
+        };
+        if step != Vec2::ZERO {
+            // Steering by hand drops any planned walk.
+            self.goal = None;
+            self.path.clear();
+            self.maybe_move_by(step);
+        } else if !self.path.is_empty() {
+            self.follow_path();
+        }
+        // Reset input for next step.
         self.input = Default::default();
     }
 
-    fn load(&mut self) {
-        self.assets = Some(Assets::load(&self.game_metrics));
-        let Self { world, .. } = self;
-        self.facing_x = 1.0;
-        self.pos = Vec2::floor(world.grid.size() * 0.5);
+    /// Pans and zooms the open map, closing it on a tap.
+    fn handle_map_input(&mut self, mouse: Vec2) {
+        // Show or hide animals.
+        if is_key_pressed(KeyCode::Tab) {
+            self.settings.sightings = !self.settings.sightings;
+            self.save_settings();
+        }
+        let Self {
+            map,
+            screen_metrics,
+            world,
+            ..
+        } = self;
+        // Keys pan at a steady pace on screen, whatever the zoom.
+        let down = |a, b| is_key_down(a) || is_key_down(b);
+        let keys = Input {
+            down: down(KeyCode::Down, KeyCode::S),
+            left: down(KeyCode::Left, KeyCode::A),
+            right: down(KeyCode::Right, KeyCode::D),
+            up: down(KeyCode::Up, KeyCode::W),
+        };
+        map.pan(keys.direction() * 60.0 * get_frame_time());
+        let wheel = mouse_wheel().1;
+        if wheel > 0.0 || is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+            map.zoom_by(0.8, world);
+        }
+        if wheel < 0.0 || is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+            map.zoom_by(1.25, world);
+        }
+        // Drag to pan, or tap to close.
+        if is_mouse_button_pressed(MouseButton::Left) {
+            self.drag = Some((mouse, false));
+        }
+        if let Some((from, moved)) = self.drag {
+            if is_mouse_button_down(MouseButton::Left) {
+                map.pan((from - mouse) / screen_metrics.scale);
+                self.drag = Some((mouse, moved || mouse != from));
+            } else {
+                self.drag = None;
+                if !moved {
+                    self.toggle_map();
+                }
+            }
+        }
+    }
+
+    /// Picks and changes settings by keys or taps, with taps on the left or
+    /// right of a row stepping down or up. Tapping elsewhere closes.
+    fn handle_settings_input(&mut self, mouse: Vec2) {
+        let pressed = |a, b| is_key_pressed(a) || is_key_pressed(b);
+        let count = Field::ALL.len();
+        if pressed(KeyCode::Up, KeyCode::W) {
+            self.setting = (self.setting + count - 1) % count;
+        }
+        if pressed(KeyCode::Down, KeyCode::S) {
+            self.setting = (self.setting + 1) % count;
+        }
+        let mut change = None;
+        if pressed(KeyCode::Left, KeyCode::A) {
+            change = Some(false);
+        }
+        if pressed(KeyCode::Right, KeyCode::D) || pressed(KeyCode::Enter, KeyCode::Space) {
+            change = Some(true);
+        }
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let rows = self.setting_rects();
+            match rows.iter().position(|row| row.contains(mouse)) {
+                Some(index) => {
+                    self.setting = index;
+                    change = Some(mouse.x > rows[index].center().x);
+                }
+                None => self.settings_open = false,
+            }
+        }
+        if let Some(up) = change {
+            let field = Field::ALL[self.setting];
+            field.adjust(&mut self.settings, up);
+            if field == Field::Fullscreen {
+                self.fullscreen = self.settings.fullscreen;
+                set_fullscreen(self.fullscreen);
+            }
+            self.save_settings();
+        }
+    }
+
+    /// Takes the next planned step, planning again if something moved into
+    /// the way.
+    fn follow_path(&mut self) {
+        let step = self.path.remove(0).as_vec2();
+        match self.world.step(self.pos, step) {
+            Some(next) => {
+                if step.x != 0.0 {
+                    self.facing_x = step.x;
+                }
+                self.pos = next;
+            }
+            None => self.plan_path(),
+        }
+        if self.path.is_empty() {
+            self.goal = None;
+        }
+    }
+
+    fn load(&mut self, args: &Args) -> Result<(), AssetError> {
+        let dir = args.asset_dir();
+        self.assets = Some(Assets::load(dir.as_deref(), &self.game_metrics)?);
+        self.fullscreen = self.settings.fullscreen || args.fullscreen;
+        if self.fullscreen {
+            set_fullscreen(true);
+        }
+        Ok(())
+    }
+
+    /// Finds the way to the goal, taking the short way around any wrapping,
+    /// or gives up on the goal if there's no way.
+    fn plan_path(&mut self) {
+        let Some(goal) = self.goal else {
+            self.path.clear();
+            return;
+        };
+        let goal = self.pos + self.world.offset(self.pos, goal.as_vec2());
+        let path = self.world.find_path(self.pos.as_ivec2(), goal.as_ivec2());
+        self.path = path.unwrap_or_default();
+        if self.path.is_empty() {
+            self.goal = None;
+        }
+    }
+
+    /// Goes back to the last save, if there is one.
+    fn restore(&mut self) {
+        let Some(path) = SaveData::path() else {
+            warn!("Nowhere to load from");
+            return;
+        };
+        match SaveData::read(&path).and_then(|save| Self::from_save(save, self.settings.clone())) {
+            Ok(game) => {
+                let Self {
+                    facing_x,
+                    pos,
+                    world,
+                    ..
+                } = game;
+                self.draw_pos = pos;
+                self.facing_x = facing_x;
+                self.goal = None;
+                self.path.clear();
+                self.pos = pos;
+                // Back in the saved world, so saving can go over it again.
+                self.saving = true;
+                self.world = world;
+                info!("Loaded {}", path.display());
+            }
+            Err(err) => warn!("Couldn't load {}: {}", path.display(), err),
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = SaveData::path() else {
+            warn!("Nowhere to save");
+            return;
+        };
+        if !self.saving {
+            warn!("Not saving this one-off world over {}", path.display());
+            return;
+        }
+        match self.save_data().write(&path) {
+            Ok(()) => info!("Saved {}", path.display()),
+            Err(err) => warn!("Couldn't save {}: {}", path.display(), err),
+        }
+    }
+
+    fn save_settings(&self) {
+        let Some(path) = Settings::path() else {
+            warn!("Nowhere to save settings");
+            return;
+        };
+        if let Err(err) = self.settings.write(&path) {
+            warn!("Couldn't save {}: {}", path.display(), err);
+        }
     }
 
     fn maybe_move_by(&mut self, vec: Vec2) {
-        let next = self.pos + vec;
         if vec.x != 0.0 {
             self.facing_x = vec.x;
         }
-        if !self.world.occupied(next) {
+        // Slide along one axis when a diagonal is blocked.
+        let tries = [vec, vec2(vec.x, 0.0), vec2(0.0, vec.y)];
+        let next = tries
+            .into_iter()
+            .filter(|&by| by != Vec2::ZERO)
+            .find_map(|by| self.world.step(self.pos, by));
+        if let Some(next) = next {
             self.pos = next;
         }
     }
@@ -291,21 +1037,107 @@ impl Game {
         let step_y = vec2(0.0, icon_size + 2.0 * gap.y);
         // Up/Down
         // TODO Force a common button size?
+        // Buttons act on press rather than release, so holding repeats.
+        let button = |pos: Vec2, label: &str| {
+            root_ui().button(pos, label);
+            pointer_at(pos, icon_size)
+        };
         let pos = vec2(0.0, screen_metrics.ui_start.y) + gap;
-        if root_ui().button(pos, "\u{e803}") {
-            self.input.up = true;
-        }
-        if root_ui().button(pos + step_y, "\u{e800}") {
-            self.input.down = true;
-        }
+        let up = button(pos, "\u{e803}");
+        let down = button(pos + step_y, "\u{e800}");
         // Left/Right
         let gap = vec2(4.0, 5.0) * screen_metrics.scale;
         let pos = vec2(screen_size.x, screen_metrics.ui_start.y) + vec2(-gap.x - icon_size, gap.y);
-        if root_ui().button(pos, "\u{e801}") {
-            self.input.left = true;
+        let left = button(pos, "\u{e801}");
+        let right = button(pos + step_y, "\u{e802}");
+        // Settings, opened from input handling like the minimap.
+        root_ui().button(self.cog_rect().point(), "\u{e804}");
+        for (input, held, (pressed, down)) in [
+            (&mut self.input.up, &mut self.held.up, up),
+            (&mut self.input.down, &mut self.held.down, down),
+            (&mut self.input.left, &mut self.held.left, left),
+            (&mut self.input.right, &mut self.held.right, right),
+        ] {
+            *input |= pressed;
+            *held |= down;
         }
-        if root_ui().button(pos + step_y, "\u{e802}") {
-            self.input.right = true;
+    }
+
+    /// The settings button, below the right arrow.
+    fn cog_rect(&self) -> Rect {
+        let Self { screen_metrics, .. } = self;
+        let icon_size = screen_metrics.icon_size;
+        let screen_size = Vec2::from_array(screen_size().into());
+        let gap = vec2(4.0, 5.0) * screen_metrics.scale;
+        let step_y = icon_size + 2.0 * gap.y;
+        let pos = vec2(screen_size.x - gap.x - icon_size, screen_metrics.ui_start.y)
+            + vec2(0.0, gap.y + 2.0 * step_y);
+        Rect::new(pos.x, pos.y, icon_size, icon_size)
+    }
+
+    fn map_rect(&self) -> Rect {
+        let Self { screen_metrics, .. } = self;
+        let start = screen_metrics.full_start;
+        let size = screen_metrics.full_size;
+        Rect::new(start.x, start.y, size.x, size.y)
+    }
+
+    /// The minimap's place, in the top right corner of the sky.
+    fn minimap_rect(&self) -> Rect {
+        let Self {
+            game_metrics,
+            screen_metrics,
+            ..
+        } = self;
+        let size = MINIMAP_SIZE.as_vec2();
+        let gap = 2.0;
+        let start = vec2(game_metrics.full_size_px.x - size.x - gap, gap);
+        let start = screen_metrics.full_start + start * screen_metrics.scale;
+        let size = size * screen_metrics.scale;
+        Rect::new(start.x, start.y, size.x, size.y)
+    }
+
+    /// Rows of the settings screen, one per field, centered in the scene.
+    fn setting_rects(&self) -> Vec<Rect> {
+        let Self { screen_metrics, .. } = self;
+        let full = self.map_rect();
+        let scale = screen_metrics.scale;
+        let size = vec2(full.w - 30.0 * scale.x, 10.0 * scale.y);
+        let top = full.center().y - size.y * Field::ALL.len() as f32 * 0.5;
+        (0..Field::ALL.len())
+            .map(|index| {
+                let y = top + index as f32 * size.y;
+                Rect::new(full.center().x - size.x * 0.5, y, size.x, size.y)
+            })
+            .collect()
+    }
+
+    fn toggle_map(&mut self) {
+        self.map_open = !self.map_open;
+        self.drag = None;
+        if self.map_open {
+            self.map.center = self.draw_pos;
+        }
+    }
+
+    fn update_maps(&mut self) {
+        self.minimap.center = self.draw_pos;
+        self.minimap.update(&self.world);
+        if self.map_open {
+            self.map.update(&self.world);
+        }
+    }
+
+    /// Walks the drawn position toward the logical one.
+    fn update_motion(&mut self) {
+        let offset = self.world.offset(self.draw_pos, self.pos);
+        let step = self.settings.move_speed * get_frame_time();
+        // Jump straight there after anything but a single step.
+        if offset.length() <= step || offset.abs().max_element() > 1.0 {
+            self.draw_pos = self.pos;
+        } else {
+            self.draw_pos += offset.normalize() * step;
+            self.walked += step;
         }
     }
 
@@ -347,6 +1179,19 @@ impl Game {
         }
     }
 
+    fn update_world(&mut self) {
+        const TICK_SECONDS: f32 = 0.25;
+        self.world.update_chunks(self.pos);
+        let (start, end) = self.camera().ground();
+        self.world.explore(start, end);
+        // Cap catching up so a long stall doesn't freeze the game further.
+        self.tick_time = (self.tick_time + get_frame_time()).min(1.0);
+        while self.tick_time >= TICK_SECONDS {
+            self.world.tick(self.pos);
+            self.tick_time -= TICK_SECONDS;
+        }
+    }
+
     fn update_skin(&mut self) {
         let button_style = root_ui()
             .style_builder()
@@ -366,3 +1211,20 @@ impl Game {
         });
     }
 }
+
+/// Checks for a mouse or touch on an icon button, returning whether it was
+/// just pressed and whether it's held down.
+fn pointer_at(pos: Vec2, icon_size: f32) -> (bool, bool) {
+    let rect = Rect::new(pos.x, pos.y, icon_size, icon_size);
+    let mouse = Vec2::from_array(mouse_position().into());
+    if rect.contains(mouse) {
+        let pressed = is_mouse_button_pressed(MouseButton::Left);
+        return (pressed, pressed || is_mouse_button_down(MouseButton::Left));
+    }
+    let touches = touches();
+    let mut touches = touches.iter().filter(|touch| rect.contains(touch.position));
+    let pressed = touches
+        .clone()
+        .any(|touch| touch.phase == TouchPhase::Started);
+    (pressed, touches.next().is_some())
+}
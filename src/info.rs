@@ -58,12 +58,6 @@ pub struct ScreenMetrics {
     pub ui_start: Vec2,
 }
 
-impl ScreenMetrics {
-    pub fn tile(&self, vec: Vec2) -> Vec2 {
-        self.ground_start + (vec + vec2(0.0, -1.0)) * self.tile_size
-    }
-}
-
 impl Default for GameMetrics {
     fn default() -> Self {
         Self::new()
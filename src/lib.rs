@@ -0,0 +1,21 @@
+//! The world of Green Island, with its generation, simulation and saves,
+//! apart from any window, for the game and any other tools to build on.
+
+pub mod arena;
+pub mod atlas;
+pub mod biome;
+pub mod camera;
+pub mod cli;
+pub mod clock;
+pub mod ecology;
+pub mod explore;
+pub mod info;
+pub mod map;
+pub mod noise;
+pub mod path;
+pub mod save;
+pub mod settings;
+pub mod sim;
+pub mod stats;
+pub mod weather;
+pub mod world;
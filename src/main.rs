@@ -1,27 +1,104 @@
+use args::Args;
+use clap::Parser;
 use game::Game;
+use greeny::save::{SaveData, set_aside};
+use greeny::settings::Settings;
+use greeny::world::{Edge, World};
 use macroquad::prelude::*;
 
+mod args;
 mod assets;
 mod game;
-mod info;
-mod world;
+mod menu;
+mod parallax;
+mod view;
 
-fn window_conf() -> Conf {
+fn window_conf(args: &Args) -> Conf {
     Conf {
-        // fullscreen: true,
-        window_height: 480,
-        window_width: 840,
+        fullscreen: args.fullscreen,
+        window_height: args.window.y,
+        window_width: args.window.x,
         window_title: "Green Island".to_owned(),
         ..Default::default()
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
+fn clock_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+fn main() {
+    let args = Args::parse();
+    macroquad::Window::from_config(window_conf(&args), start(args));
+}
+
+async fn start(args: Args) {
     let start = get_time();
-    let mut game = Game::default();
+    let settings = match Settings::path() {
+        Some(path) => Settings::read(&path)
+            .inspect_err(|err| info!("No settings loaded from {}: {}", path.display(), err))
+            .unwrap_or_default(),
+        None => Settings::default(),
+    };
+    // Continue from the last save unless asked for a new world.
+    let save_path = SaveData::path();
+    let mut keep_save = false;
+    let game = match (&save_path, args.new_world()) {
+        (Some(path), false) if path.exists() => {
+            match SaveData::read(path).and_then(|save| Game::from_save(save, settings.clone())) {
+                Ok(game) => Some(game),
+                Err(err) => {
+                    warn!("Couldn't load {}: {}", path.display(), err);
+                    // Don't let saving the new world destroy the old one.
+                    match set_aside(path) {
+                        Ok(backup) => warn!("Kept it as {}", backup.display()),
+                        Err(err) => {
+                            warn!("Couldn't set it aside: {}", err);
+                            keep_save = true;
+                        }
+                    }
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let mut game = match game {
+        Some(game) => game,
+        None => {
+            let seed = args.seed.unwrap_or_else(clock_seed);
+            let size = args.size.unwrap_or(World::DEFAULT_SIZE);
+            let mut world = World::generate(seed, Some(size));
+            if args.wrap {
+                world.edge = Edge::Wrap;
+            }
+            Game::new(world, settings)
+        }
+    };
+    if args.new_world()
+        && let Some(path) = save_path.filter(|path| path.exists())
+    {
+        warn!("Leaving {} alone for this new world", path.display());
+        keep_save = true;
+    }
+    if keep_save {
+        game.keep_save();
+    }
+    if let Some(pos) = args.start
+        && !game.place_player(pos)
+    {
+        error!("Start {} is outside the world", pos);
+        std::process::exit(1);
+    }
+    info!("Seed {}", game.seed());
     let done = get_time();
     let init_ms = (done - start) * 1e3;
     info!("Started in {:.3}ms", init_ms);
-    game.run().await;
+    if let Err(err) = game.run(&args).await {
+        error!("Failed to load assets: {}", err);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,209 @@
+use macroquad::prelude::*;
+
+use crate::atlas::{Sprite, TileInfo};
+use crate::biome::Biome;
+use crate::world::{Animal, AnimalKind, ChunkGrid, Grid, Occupant, Plant, Tile, World};
+
+/// Plant density past which map colors stop getting greener.
+const DENSE: f32 = 0.3;
+
+/// Color of places not yet seen.
+const FOG: Color = Color::new(0.16, 0.14, 0.13, 1.0);
+
+impl AnimalKind {
+    pub fn color(self) -> Color {
+        Color::from_hex(match self {
+            AnimalKind::Bead => 0x2B2B2B,
+            AnimalKind::Bob => 0xB5834F,
+            AnimalKind::Coyote => 0xA08C6E,
+            AnimalKind::Jack => 0x8E7B66,
+            AnimalKind::Javelina => 0x4A3F38,
+            AnimalKind::Rattler => 0xC9A56B,
+            AnimalKind::Runner => 0x5B6E8C,
+            AnimalKind::Turkey => 0x7A3B2E,
+        })
+    }
+}
+
+impl Plant {
+    pub fn color(self) -> Color {
+        Color::from_hex(match self {
+            Plant::NopalBig => 0x5E8C3A,
+            Plant::NopalSmall => 0x7FA650,
+            Plant::Ocotillo => 0x7C6A4A,
+            Plant::Saguaro => 0x3F6B3A,
+        })
+    }
+}
+
+impl Biome {
+    /// Bare ground color, for maps.
+    pub fn color(self) -> Color {
+        Color::from_hex(match self {
+            Biome::CreosoteFlats => 0xC5AD95,
+            Biome::MesquiteWash => 0xD8C9A3,
+            Biome::OcotilloRidge => 0x9E8069,
+            Biome::SaguaroBajada => 0xB59A78,
+        })
+    }
+
+    /// Share of tiles expected to hold a plant.
+    pub fn plant_density(self) -> f32 {
+        self.plant_odds().iter().map(|(_, chance)| chance).sum()
+    }
+}
+
+/// Colors a block of tiles by biome, greened by how many plants grow there.
+/// Loaded chunks are sampled, and the rest go by what the biome would grow.
+/// Blocks the player hasn't seen any of stay fogged.
+pub fn block_color(world: &World, start: Vec2, size: f32) -> Color {
+    let middle = world.wrap((start + size * 0.5).floor().as_ivec2());
+    if !world.contains(middle) {
+        return BLACK;
+    }
+    let biome = world.biome_at(middle);
+    let samples = size.ceil().clamp(1.0, 4.0) as i32;
+    let mut plants = 0;
+    let mut loaded = 0;
+    let mut seen = false;
+    for y in 0..samples {
+        for x in 0..samples {
+            let at = start + (vec2(x as f32, y as f32) + 0.5) * size / samples as f32;
+            let at = world.wrap(at.floor().as_ivec2());
+            seen |= world.explored.contains(at);
+            if let Some(tile) = world.grid.get(at) {
+                loaded += 1;
+                plants += matches!(tile.occupant, Some(Occupant::Plant(_))) as i32;
+            }
+        }
+    }
+    if !seen {
+        return FOG;
+    }
+    let density = match loaded {
+        0 => biome.plant_density(),
+        _ => plants as f32 / loaded as f32,
+    };
+    let green = Color::from_hex(0x4F7A45).to_vec();
+    let color = biome
+        .color()
+        .to_vec()
+        .lerp(green, (density / DENSE).min(1.0) * 0.8);
+    Color::from_vec(color)
+}
+
+/// Paints a whole world as generated, in blocks of `scale` pixels per tile
+/// colored by what stands there. Gives `None` for an unbounded world or one
+/// too big for an image.
+pub fn paint_tiles(world: &World, scale: u16) -> Option<Image> {
+    let size = image_size(world, Vec2::splat(scale as f32))?;
+    let mut image = Image::gen_image_color(size.x, size.y, BLACK);
+    let scale = scale as u32;
+    let mut fill = |pos: IVec2, color: Color| {
+        let corner = pos.as_uvec2() * scale;
+        for y in corner.y..corner.y + scale {
+            for x in corner.x..corner.x + scale {
+                image.set_pixel(x, y, color);
+            }
+        }
+    };
+    each_chunk(world, |start, chunk, animals| {
+        for (pos, tile) in tiles(world, start, &chunk) {
+            let color = match tile.occupant {
+                Some(Occupant::Plant(plant)) => plant.color(),
+                _ => world.biome_at(pos).color(),
+            };
+            fill(pos, color);
+        }
+        for animal in animals {
+            fill(animal.pos.as_ivec2(), animal.kind.color());
+        }
+    });
+    Some(image)
+}
+
+/// Paints a whole world as generated with its sprites from the tile sheet,
+/// over ground colored by biome. Gives `None` for an unbounded world or one
+/// too big for an image.
+pub fn paint_sprites(
+    world: &World,
+    sheet: &Image,
+    tile_info: &TileInfo,
+    tile_size: Vec2,
+) -> Option<Image> {
+    let size = image_size(world, tile_size)?;
+    let mut image = Image::gen_image_color(size.x, size.y, BLACK);
+    let mut sprites: Vec<(IVec2, &Sprite)> = vec![];
+    each_chunk(world, |start, chunk, animals| {
+        for (pos, tile) in tiles(world, start, &chunk) {
+            let corner = (pos.as_vec2() * tile_size).as_uvec2();
+            let color = world.biome_at(pos).color();
+            for y in corner.y..corner.y + tile_size.y as u32 {
+                for x in corner.x..corner.x + tile_size.x as u32 {
+                    image.set_pixel(x, y, color);
+                }
+            }
+            if let Some(Occupant::Plant(plant)) = tile.occupant {
+                sprites.push((pos, tile_info.plant(plant)));
+            }
+        }
+        for animal in animals {
+            sprites.push((animal.pos.as_ivec2(), tile_info.animal(animal.kind)));
+        }
+    });
+    // Back to front, so nearer sprites cover farther ones.
+    sprites.sort_by_key(|(pos, _)| (pos.y, pos.x));
+    for (pos, sprite) in sprites {
+        let source = sprite.rect();
+        let corner = (pos.as_vec2() * tile_size - sprite.anchor).as_ivec2();
+        for y in 0..source.h as i32 {
+            for x in 0..source.w as i32 {
+                let to = corner + ivec2(x, y);
+                if to.cmplt(IVec2::ZERO).any() || to.cmpge(size.as_ivec2()).any() {
+                    continue;
+                }
+                let from = source.point().as_ivec2() + ivec2(x, y);
+                let color = sheet.get_pixel(from.x as u32, from.y as u32);
+                let (x, y) = (to.x as u32, to.y as u32);
+                let under = image.get_pixel(x, y).to_vec();
+                let over = color.to_vec().with_w(1.0);
+                image.set_pixel(x, y, Color::from_vec(under.lerp(over, color.a)));
+            }
+        }
+    }
+    Some(image)
+}
+
+/// Generates every chunk of a bounded world in turn, without loading any.
+fn each_chunk(world: &World, mut visit: impl FnMut(IVec2, Grid<Tile>, Vec<Animal>)) {
+    let Some(size) = world.size else {
+        return;
+    };
+    let chunks = (size + ChunkGrid::CHUNK_SIZE - 1) / ChunkGrid::CHUNK_SIZE;
+    for y in 0..chunks.y {
+        for x in 0..chunks.x {
+            let coord = ivec2(x, y);
+            let (chunk, animals) = world.generate_chunk(coord);
+            visit(coord * ChunkGrid::CHUNK_SIZE, chunk, animals);
+        }
+    }
+}
+
+/// Size in pixels of a whole world at some size per tile, if it fits.
+fn image_size(world: &World, tile_size: Vec2) -> Option<U16Vec2> {
+    let size = world.size?.as_vec2() * tile_size;
+    let fits = size.cmpgt(Vec2::ZERO).all() && size.cmple(Vec2::splat(u16::MAX as f32)).all();
+    fits.then(|| size.as_u16vec2())
+}
+
+/// Tiles of a generated chunk that fall inside the world, with positions.
+fn tiles<'a>(
+    world: &'a World,
+    start: IVec2,
+    chunk: &'a Grid<Tile>,
+) -> impl Iterator<Item = (IVec2, Tile)> + 'a {
+    (0..chunk.size_y())
+        .flat_map(move |y| (0..chunk.size_x()).map(move |x| (x, y)))
+        .map(move |(x, y)| (start + ivec2(x as i32, y as i32), chunk.at(x, y)))
+        .filter(|&(pos, _)| world.contains(pos))
+}
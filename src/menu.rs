@@ -0,0 +1,66 @@
+use std::ops::RangeInclusive;
+
+use greeny::settings::Settings;
+
+/// A setting that can be changed from the settings screen.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    Fullscreen,
+    MoveSpeed,
+    RepeatDelay,
+    RepeatRate,
+    Sightings,
+}
+
+impl Field {
+    /// Every field, in the order listed.
+    pub const ALL: [Field; 5] = [
+        Field::MoveSpeed,
+        Field::RepeatDelay,
+        Field::RepeatRate,
+        Field::Sightings,
+        Field::Fullscreen,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Fullscreen => "Fullscreen",
+            Field::MoveSpeed => "Walk speed",
+            Field::RepeatDelay => "Repeat delay",
+            Field::RepeatRate => "Repeat rate",
+            Field::Sightings => "Map animals",
+        }
+    }
+
+    pub fn value(self, settings: &Settings) -> String {
+        let on_off = |on| match on {
+            true => "On".to_owned(),
+            false => "Off".to_owned(),
+        };
+        match self {
+            Field::Fullscreen => on_off(settings.fullscreen),
+            Field::MoveSpeed => format!("{:.0} tiles/s", settings.move_speed),
+            Field::RepeatDelay => format!("{:.2} s", settings.repeat_delay),
+            Field::RepeatRate => format!("{:.0} steps/s", settings.repeat_rate),
+            Field::Sightings => on_off(settings.sightings),
+        }
+    }
+
+    /// Steps a number down or up within its range, or flips a switch either
+    /// way.
+    pub fn adjust(self, settings: &mut Settings, up: bool) {
+        let step = |value: &mut f32, by: f32, range: RangeInclusive<f32>| {
+            let next = *value + if up { by } else { -by };
+            // Round off drift from repeated steps.
+            let next = (next / by).round() * by;
+            *value = next.clamp(*range.start(), *range.end());
+        };
+        match self {
+            Field::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            Field::MoveSpeed => step(&mut settings.move_speed, 1.0, Settings::MOVE_SPEEDS),
+            Field::RepeatDelay => step(&mut settings.repeat_delay, 0.05, Settings::REPEAT_DELAYS),
+            Field::RepeatRate => step(&mut settings.repeat_rate, 1.0, Settings::REPEAT_RATES),
+            Field::Sightings => settings.sightings = !settings.sightings,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use macroquad::math::Vec2;
+
+/// Hashes a seed and integer coordinates into well-mixed bits, so that any
+/// spot in the world can derive its own randomness without global state.
+pub fn hash2(seed: u64, x: i32, y: i32) -> u64 {
+    let mut h = seed ^ ((x as u32 as u64) << 32) ^ (y as u32 as u64);
+    // SplitMix64 finalizer.
+    h = h.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// Returns smooth value noise in 0..1 with one random value per integer
+/// lattice point.
+pub fn value2(seed: u64, pos: Vec2) -> f32 {
+    let cell = pos.floor();
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let corner = |dx: i32, dy: i32| (hash2(seed, x + dx, y + dy) >> 40) as f32 / (1 << 24) as f32;
+    // Smoothstep the fraction for softer borders between cells.
+    let t = pos - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let top = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * t.x;
+    let bottom = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * t.x;
+    top + (bottom - top) * t.y
+}
+
+/// Sums octaves of value noise, each at double the frequency and half the
+/// weight of the last, normalized back into 0..1.
+pub fn fractal2(seed: u64, pos: Vec2, octaves: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut weight = 1.0;
+    let mut total = 0.0;
+    for octave in 0..octaves {
+        let scale = (1 << octave) as f32;
+        sum += weight * value2(seed.wrapping_add(octave as u64), pos * scale);
+        total += weight;
+        weight *= 0.5;
+    }
+    sum / total
+}
@@ -0,0 +1,60 @@
+use std::f32::consts::TAU;
+
+use greeny::noise::hash2;
+
+/// A backdrop band that scrolls slower than the ground, so it seems far off.
+#[derive(Clone, Copy, Debug)]
+pub struct Layer {
+    /// Width in pixels before the layer repeats.
+    pub period: f32,
+    /// Pixels scrolled per pixel the ground scrolls.
+    pub rate: f32,
+}
+
+impl Layer {
+    /// The mountains texture, mirrored every other copy so its ends meet.
+    pub const MOUNTAINS: Layer = Layer {
+        period: 300.0,
+        rate: 0.1,
+    };
+
+    pub const RIDGES: Layer = Layer {
+        period: 600.0,
+        rate: 0.3,
+    };
+
+    /// Small plants just past the horizon.
+    pub const PLANTS: Layer = Layer {
+        period: 400.0,
+        rate: 0.6,
+    };
+
+    /// How far the layer has scrolled, from 0 up to its period, with `ground`
+    /// as pixels the ground has scrolled. Going once around a world that wraps
+    /// at `wrap` pixels also goes a whole number of times around the layer, so
+    /// the two line up again.
+    pub fn scroll(self, ground: f32, wrap: Option<f32>) -> f32 {
+        let scroll = match wrap {
+            Some(wrap) => {
+                let repeats = (wrap * self.rate / self.period).round().max(1.0);
+                ground / wrap * repeats * self.period
+            }
+            None => ground * self.rate,
+        };
+        scroll.rem_euclid(self.period)
+    }
+}
+
+/// Height of the ridge line at a point along the layer, in pixels, with only
+/// whole waves per period so it repeats without a seam.
+pub fn ridge_height(seed: u64, at: f32) -> f32 {
+    let mut height = 5.0;
+    for (wave, (cycles, size)) in [(3.0, 2.5), (7.0, 1.5), (17.0, 0.7)]
+        .into_iter()
+        .enumerate()
+    {
+        let phase = (hash2(seed, wave as i32, -2) & 0xFFFF) as f32 / 65536.0;
+        height += size * (TAU * (cycles * at / Layer::RIDGES.period + phase)).sin();
+    }
+    height
+}
@@ -0,0 +1,183 @@
+use macroquad::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::f32::consts::SQRT_2;
+
+use crate::world::World;
+
+/// Steps to all eight neighbors, orthogonal first.
+pub const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(0, -1),
+    IVec2::new(1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, -1),
+    IVec2::new(1, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Finds the cheapest path from `start` to `goal` by A*, as the steps to take
+/// in order. The `cost` hook gives the cost of stepping from a tile to a
+/// neighbor, or `None` if blocked, and should be no less than the distance
+/// moved for the path to be the best. Gives up after visiting `limit` tiles.
+pub fn find_path(
+    start: IVec2,
+    goal: IVec2,
+    limit: usize,
+    mut cost: impl FnMut(IVec2, IVec2) -> Option<f32>,
+) -> Option<Vec<IVec2>> {
+    let mut best = HashMap::from([(start, 0.0)]);
+    let mut came_from = HashMap::new();
+    let mut done = HashSet::new();
+    let mut open = BinaryHeap::from([Node {
+        estimate: distance(start, goal),
+        pos: start,
+    }]);
+    while let Some(Node { pos, .. }) = open.pop() {
+        if pos == goal {
+            let mut steps = vec![];
+            let mut at = goal;
+            while let Some(&prev) = came_from.get(&at) {
+                steps.push(at - prev);
+                at = prev;
+            }
+            steps.reverse();
+            return Some(steps);
+        }
+        // Skip stale entries for tiles already reached more cheaply.
+        if !done.insert(pos) {
+            continue;
+        }
+        if done.len() > limit {
+            return None;
+        }
+        let so_far = best[&pos];
+        for step in NEIGHBORS {
+            let next = pos + step;
+            let Some(step_cost) = cost(pos, next) else {
+                continue;
+            };
+            let total = so_far + step_cost;
+            if best.get(&next).is_none_or(|&old| total < old) {
+                best.insert(next, total);
+                came_from.insert(next, pos);
+                open.push(Node {
+                    estimate: total + distance(next, goal),
+                    pos: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Shortest distance with diagonal steps and nothing in the way.
+fn distance(a: IVec2, b: IVec2) -> f32 {
+    let offset = (a - b).abs();
+    let (long, short) = (offset.max_element(), offset.min_element());
+    (long - short) as f32 + short as f32 * SQRT_2
+}
+
+/// An open tile, ordered so the heap pops the lowest estimate first.
+#[derive(Clone, Copy, PartialEq)]
+struct Node {
+    estimate: f32,
+    pos: IVec2,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            // Break ties the same way every time.
+            .then_with(|| (other.pos.x, other.pos.y).cmp(&(self.pos.x, self.pos.y)))
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl World {
+    /// Plans a walk around plants and animals, by the same rules as
+    /// [`World::step`].
+    pub fn find_path(&self, from: IVec2, to: IVec2) -> Option<Vec<IVec2>> {
+        const LIMIT: usize = 2000;
+        find_path(from, to, LIMIT, |pos, next| {
+            let by = next - pos;
+            self.step(pos.as_vec2(), by.as_vec2())?;
+            Some(match by.x != 0 && by.y != 0 {
+                true => SQRT_2,
+                false => 1.0,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Edge;
+    use crate::world::tests::small_world;
+
+    /// Costs for an open plane with some tiles blocked.
+    fn around(blocked: &[IVec2]) -> impl FnMut(IVec2, IVec2) -> Option<f32> {
+        |pos: IVec2, next: IVec2| {
+            (!blocked.contains(&next)).then(|| pos.as_vec2().distance(next.as_vec2()))
+        }
+    }
+
+    #[test]
+    fn goes_straight_when_open() {
+        let path = find_path(IVec2::ZERO, ivec2(5, 0), 100, around(&[])).unwrap();
+        assert_eq!(path, vec![ivec2(1, 0); 5]);
+    }
+
+    #[test]
+    fn detours_around_a_wall() {
+        let wall: Vec<IVec2> = (-3..=3).map(|y| ivec2(2, y)).collect();
+        let path = find_path(IVec2::ZERO, ivec2(4, 0), 1000, around(&wall)).unwrap();
+        let mut pos = IVec2::ZERO;
+        for step in &path {
+            pos += *step;
+            assert!(!wall.contains(&pos), "through the wall at {pos}");
+        }
+        assert_eq!(pos, ivec2(4, 0));
+        // Around the end of the wall and back, with diagonals where they help.
+        assert_eq!(path.len(), 8);
+    }
+
+    #[test]
+    fn gives_up_on_an_enclosed_goal() {
+        let ring: Vec<IVec2> = NEIGHBORS.iter().map(|&step| ivec2(10, 0) + step).collect();
+        assert_eq!(
+            find_path(IVec2::ZERO, ivec2(10, 0), 500, around(&ring)),
+            None
+        );
+    }
+
+    #[test]
+    fn refuses_to_cut_a_pinched_corner() {
+        let world = small_world(Edge::Solid, &[ivec2(2, 1), ivec2(1, 2)]);
+        let path = world.find_path(ivec2(1, 1), ivec2(2, 2)).unwrap();
+        assert_ne!(path, vec![ivec2(1, 1)]);
+        assert_eq!(path.iter().sum::<IVec2>(), ivec2(1, 1));
+    }
+
+    #[test]
+    fn crosses_a_wrapping_edge() {
+        let world = small_world(Edge::Wrap, &[]);
+        assert_eq!(
+            world.find_path(ivec2(0, 3), ivec2(-1, 3)),
+            Some(vec![ivec2(-1, 0)])
+        );
+        let solid = small_world(Edge::Solid, &[]);
+        assert_eq!(solid.find_path(ivec2(0, 3), ivec2(-1, 3)), None);
+    }
+}
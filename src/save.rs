@@ -0,0 +1,249 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::arena::Arena;
+use crate::clock::Clock;
+use crate::explore::Explored;
+use crate::noise::hash2;
+use crate::weather::Weather;
+use crate::world::{Animal, ChunkGrid, Edge, Occupant, Plant, World};
+
+/// Bumped whenever the layout changes, so old saves are refused rather than
+/// misread.
+pub const SAVE_VERSION: u32 = 1;
+
+pub const SAVE_FILE: &str = "save.json";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SaveData {
+    pub version: u32,
+    pub player: PlayerSave,
+    pub world: WorldSave,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PlayerSave {
+    pub facing_x: f32,
+    pub pos: Vec2,
+}
+
+/// The world as its seed plus whatever differs from fresh generation.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WorldSave {
+    pub animals: Vec<Animal>,
+    /// Loaded chunks, in sorted order.
+    pub chunks: Vec<IVec2>,
+    pub clock: Clock,
+    pub edge: Edge,
+    /// Seen tiles, as packed by [`Explored::pack`].
+    #[serde(default)]
+    pub explored: Vec<(IVec2, String)>,
+    /// Tiles whose plants differ from generation.
+    pub plants: Vec<(IVec2, Option<Plant>)>,
+    pub seed: u64,
+    pub size: Option<IVec2>,
+    pub weather: Weather,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    /// A chunk of seen tiles that doesn't parse.
+    Explored(IVec2),
+    Format(serde_json::Error),
+    Io(io::Error),
+    Version(u32),
+}
+
+impl SaveData {
+    /// The save file in the platform's data dir, if it has one.
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("greeny").join(SAVE_FILE))
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SaveError> {
+        let text = fs::read_to_string(path)?;
+        // Check the version before the rest, which might not parse otherwise.
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        let Version { version } = serde_json::from_str(&text)?;
+        if version != SAVE_VERSION {
+            return Err(SaveError::Version(version));
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        replace_file(path, &serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Writes a file, making its dir if needed. The text goes to a temp file
+/// first, so a failed write can't clobber the old file.
+pub fn replace_file(path: impl AsRef<Path>, text: &str) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, text)?;
+    fs::rename(temp, path)
+}
+
+/// Moves an unreadable file aside so nothing writes over it, returning
+/// where it went.
+pub fn set_aside(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let backup = path.with_extension("bak");
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+impl World {
+    /// Rebuilds a world from its save, regenerating the saved chunks and then
+    /// restoring animals and changed plants.
+    pub fn from_save(save: WorldSave) -> Result<Self, SaveError> {
+        let mut world = World::generate(save.seed, save.size);
+        world.edge = save.edge;
+        world.explored = Explored::unpack(&save.explored)?;
+        world.clock = save.clock;
+        world.weather = save.weather;
+        // The old generator state isn't kept, so continue from the clock.
+        let rng = RandGenerator::new();
+        rng.srand(hash2(
+            save.seed,
+            save.clock.ticks as i32,
+            (save.clock.ticks >> 32) as i32,
+        ));
+        world.rng = rng;
+        for coord in save.chunks {
+            let (chunk, _) = world.generate_chunk(coord);
+            world.grid.chunks.insert(coord, chunk);
+        }
+        for (pos, plant) in save.plants {
+            if let Some(tile) = world.grid.mut_at(pos) {
+                tile.occupant = plant.map(Occupant::Plant);
+            }
+        }
+        world.animals = Arena::new();
+        for animal in save.animals {
+            if let Some(tile) = world.grid.mut_at(animal.pos.as_ivec2()) {
+                tile.occupant = Some(Occupant::Animal(world.animals.insert(animal)));
+            }
+        }
+        Ok(world)
+    }
+
+    pub fn save(&self) -> WorldSave {
+        let mut chunks: Vec<IVec2> = self.grid.chunks.keys().copied().collect();
+        chunks.sort_by_key(|coord| (coord.x, coord.y));
+        let mut plants = vec![];
+        for &coord in &chunks {
+            let (fresh, _) = self.generate_chunk(coord);
+            let chunk = &self.grid.chunks[&coord];
+            let size = ChunkGrid::CHUNK_SIZE as usize;
+            for x in 0..size {
+                for y in 0..size {
+                    let plant = |occupant| match occupant {
+                        Some(Occupant::Plant(plant)) => Some(plant),
+                        _ => None,
+                    };
+                    let now = plant(chunk.at(x, y).occupant);
+                    if now != plant(fresh.at(x, y).occupant) {
+                        let pos = coord * ChunkGrid::CHUNK_SIZE + ivec2(x as i32, y as i32);
+                        plants.push((pos, now));
+                    }
+                }
+            }
+        }
+        WorldSave {
+            animals: self.animals.values().copied().collect(),
+            chunks,
+            clock: self.clock,
+            edge: self.edge,
+            explored: self.explored.pack(),
+            plants,
+            seed: self.seed,
+            size: self.size,
+            weather: self.weather,
+        }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Explored(coord) => write!(f, "bad explored chunk at {coord}"),
+            SaveError::Format(err) => write!(f, "bad save format: {err}"),
+            SaveError::Io(err) => write!(f, "save file error: {err}"),
+            SaveError::Version(version) => {
+                write!(f, "save version {version} isn't {SAVE_VERSION}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Format(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_round_trips() {
+        let mut world = World::generate(11, Some(World::DEFAULT_SIZE));
+        let player = world.center();
+        world.update_chunks(player);
+        world.explore(player.as_ivec2() - 10, player.as_ivec2() + 10);
+        for _ in 0..500 {
+            world.tick(player);
+        }
+        // Clear the first plant near the player.
+        let plant = (0..ChunkGrid::CHUNK_SIZE)
+            .flat_map(|y| (0..ChunkGrid::CHUNK_SIZE).map(move |x| ivec2(x, y)))
+            .map(|offset| player.as_ivec2() + offset)
+            .find(|&pos| matches!(world.grid.at(pos).occupant, Some(Occupant::Plant(_))))
+            .unwrap();
+        world.grid.mut_at(plant).unwrap().occupant = None;
+        let saved = world.save();
+        assert_eq!(saved.plants, vec![(plant, None)]);
+        assert!(!saved.animals.is_empty());
+        assert!(!saved.explored.is_empty());
+        let text = serde_json::to_string(&saved).unwrap();
+        let loaded = World::from_save(serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(loaded.save(), saved);
+        assert_eq!(loaded.explored.count(), world.explored.count());
+    }
+
+    #[test]
+    fn set_aside_keeps_contents() {
+        let dir = std::env::temp_dir().join(format!("greeny-aside-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SAVE_FILE);
+        fs::write(&path, "{\"version\": 0}").unwrap();
+        let backup = set_aside(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(backup, dir.join("save.bak"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{\"version\": 0}");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
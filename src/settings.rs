@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use crate::save::{replace_file, set_aside};
+
+/// Bumped whenever a setting changes meaning, so older files can be brought
+/// up to date as they're read.
+pub const SETTINGS_VERSION: u32 = 1;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Player preferences that carry across worlds.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub fullscreen: bool,
+    /// Walking speed in tiles per second.
+    pub move_speed: f32,
+    /// Seconds to hold a direction before steps repeat.
+    pub repeat_delay: f32,
+    /// Repeated steps per second while held, limited by walking speed.
+    pub repeat_rate: f32,
+    /// Whether maps mark animals seen nearby.
+    pub sightings: bool,
+    pub version: u32,
+    /// Keys this version doesn't know, such as from a newer one, kept so
+    /// writing the file back doesn't lose them.
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    /// Bad JSON, along with where an unreadable file was set aside, if it was.
+    Format(serde_json::Error, Option<PathBuf>),
+    Io(io::Error),
+}
+
+impl Settings {
+    pub const MOVE_SPEEDS: RangeInclusive<f32> = 2.0..=20.0;
+    pub const REPEAT_DELAYS: RangeInclusive<f32> = 0.05..=1.0;
+    pub const REPEAT_RATES: RangeInclusive<f32> = 2.0..=30.0;
+
+    /// The settings file in the platform's config dir, if it has one.
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("greeny").join(SETTINGS_FILE))
+    }
+
+    /// Reads settings, defaulting any missing and keeping any unknown, and
+    /// pulling numbers back into range. A file that doesn't parse is moved
+    /// aside so later writes don't lose it.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SettingsError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut settings: Self = match serde_json::from_str(&text) {
+            Ok(settings) => settings,
+            Err(err) => return Err(SettingsError::Format(err, set_aside(path).ok())),
+        };
+        // Older files only lack newer keys so far, which default.
+        settings.version = settings.version.max(SETTINGS_VERSION);
+        let clamp =
+            |value: f32, range: RangeInclusive<f32>| value.clamp(*range.start(), *range.end());
+        settings.move_speed = clamp(settings.move_speed, Self::MOVE_SPEEDS);
+        settings.repeat_delay = clamp(settings.repeat_delay, Self::REPEAT_DELAYS);
+        settings.repeat_rate = clamp(settings.repeat_rate, Self::REPEAT_RATES);
+        Ok(settings)
+    }
+
+    /// Writes the file, making its dir if needed.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        replace_file(path, &serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            move_speed: 8.0,
+            repeat_delay: 0.25,
+            repeat_rate: 10.0,
+            sightings: true,
+            unknown: BTreeMap::new(),
+            version: SETTINGS_VERSION,
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::Format(err, None) => write!(f, "bad settings format: {err}"),
+            SettingsError::Format(err, Some(backup)) => {
+                write!(
+                    f,
+                    "bad settings format: {err}, kept as {}",
+                    backup.display()
+                )
+            }
+            SettingsError::Io(err) => write!(f, "settings file error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+    fn from(err: io::Error) -> Self {
+        SettingsError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SettingsError {
+    fn from(err: serde_json::Error) -> Self {
+        SettingsError::Format(err, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a settings file to its own temp dir, returning the path.
+    fn settings_file(name: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("greeny-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SETTINGS_FILE);
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn unknown_keys_survive_writing() {
+        let path = settings_file("unknown", r#"{"move_speed": 6.0, "zoom": {"level": 3}}"#);
+        let settings = Settings::read(&path).unwrap();
+        assert_eq!(settings.move_speed, 6.0);
+        assert_eq!(settings.repeat_rate, Settings::default().repeat_rate);
+        assert_eq!(settings.unknown["zoom"]["level"], 3);
+        settings.write(&path).unwrap();
+        assert_eq!(Settings::read(&path).unwrap(), settings);
+    }
+
+    #[test]
+    fn newer_version_is_kept() {
+        let version = SETTINGS_VERSION + 1;
+        let path = settings_file("newer", &format!(r#"{{"version": {version}}}"#));
+        assert_eq!(Settings::read(&path).unwrap().version, version);
+        let path = settings_file("older", r#"{"version": 0}"#);
+        assert_eq!(Settings::read(&path).unwrap().version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn values_are_pulled_into_range() {
+        let text = r#"{"move_speed": 1000.0, "repeat_delay": -1.0, "repeat_rate": 0.0}"#;
+        let settings = Settings::read(settings_file("range", text)).unwrap();
+        assert_eq!(settings.move_speed, *Settings::MOVE_SPEEDS.end());
+        assert_eq!(settings.repeat_delay, *Settings::REPEAT_DELAYS.start());
+        assert_eq!(settings.repeat_rate, *Settings::REPEAT_RATES.start());
+    }
+
+    #[test]
+    fn bad_file_is_set_aside() {
+        let text = r#"{"move_speed": "fast"}"#;
+        let path = settings_file("bad", text);
+        let Err(SettingsError::Format(_, Some(backup))) = Settings::read(&path) else {
+            panic!("bad settings read");
+        };
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(backup).unwrap(), text);
+    }
+}
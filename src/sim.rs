@@ -0,0 +1,207 @@
+use macroquad::prelude::*;
+
+use crate::world::{Animal, AnimalHandle, AnimalKind, Occupant, World};
+
+pub const DIRECTIONS: [IVec2; 4] = [
+    IVec2::new(0, -1),
+    IVec2::new(1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Behavior {
+    /// Sits still for long stretches, waiting for prey.
+    Ambush,
+    /// Sprints in straight lines, then stops short.
+    Dart,
+    /// Bolts away from the player when near, otherwise hops about.
+    Flee,
+    /// Stays close to others of its kind.
+    Flock,
+    /// Holds a heading for long distances.
+    Roam,
+    /// Ambles about at random.
+    Wander,
+}
+
+impl AnimalKind {
+    pub fn behavior(self) -> Behavior {
+        match self {
+            AnimalKind::Bead => Behavior::Wander,
+            AnimalKind::Bob => Behavior::Wander,
+            AnimalKind::Coyote => Behavior::Roam,
+            AnimalKind::Jack => Behavior::Flee,
+            AnimalKind::Javelina => Behavior::Flock,
+            AnimalKind::Rattler => Behavior::Ambush,
+            AnimalKind::Runner => Behavior::Dart,
+            AnimalKind::Turkey => Behavior::Flock,
+        }
+    }
+}
+
+impl World {
+    /// Advances every loaded animal by one step of its behavior.
+    pub fn tick(&mut self, player: Vec2) {
+        self.clock.advance();
+        self.weather.advance(&self.rng);
+        let phase = self.clock.phase();
+        let weather_activity = self.weather.activity();
+        let player = player.floor().as_ivec2();
+        for handle in self.animals.handles() {
+            let mut animal = self.animals[handle];
+            if animal.rest > 0 {
+                animal.rest -= 1;
+                self.animals[handle] = animal;
+                continue;
+            }
+            // Mostly lie low outside active hours or in storms.
+            let activity = animal.kind.activity().level(phase) * weather_activity;
+            if self.rng.gen_range(0.0, 1.0) > activity {
+                continue;
+            }
+            let step = self.choose_step(&mut animal, player);
+            if step != IVec2::ZERO {
+                let to = self.wrap(animal.pos.as_ivec2() + step);
+                if to == player || !self.move_animal(handle, to) {
+                    // Blocked, so try elsewhere next time.
+                    animal.heading = IVec2::ZERO;
+                }
+            }
+            animal.pos = self.animals[handle].pos;
+            self.animals[handle] = animal;
+        }
+        self.live(player);
+    }
+
+    /// Moves the animal to an open, loaded tile, keeping the grid in step.
+    pub fn move_animal(&mut self, handle: AnimalHandle, to: IVec2) -> bool {
+        let to = self.wrap(to);
+        if !self.grid.is_loaded(to) || self.occupied(to.as_vec2()) {
+            return false;
+        }
+        let Some(animal) = self.animals.get_mut(handle) else {
+            return false;
+        };
+        let from = animal.pos.as_ivec2();
+        animal.pos = to.as_vec2();
+        if let Some(tile) = self.grid.mut_at(from) {
+            tile.occupant = None;
+        }
+        if let Some(tile) = self.grid.mut_at(to) {
+            tile.occupant = Some(Occupant::Animal(handle));
+        }
+        true
+    }
+
+    fn choose_step(&self, animal: &mut Animal, player: IVec2) -> IVec2 {
+        let pos = animal.pos;
+        let chance = |odds: f32| self.rng.gen_range(0.0, 1.0) < odds;
+        if let Some(prey) = self.nearest_prey(animal)
+            && chance(0.7)
+        {
+            return toward(prey);
+        }
+        match animal.kind.behavior() {
+            Behavior::Ambush => {
+                if chance(0.02) {
+                    animal.rest = self.rng.gen_range(20, 60);
+                    return self.random_direction();
+                }
+                IVec2::ZERO
+            }
+            Behavior::Dart => {
+                if animal.heading != IVec2::ZERO && chance(0.85) {
+                    return animal.heading;
+                }
+                animal.heading = IVec2::ZERO;
+                if chance(0.1) {
+                    animal.heading = self.random_direction();
+                } else {
+                    animal.rest = self.rng.gen_range(2, 8);
+                }
+                animal.heading
+            }
+            Behavior::Flee => {
+                let away = self.offset(player.as_vec2(), pos);
+                if away.abs().max_element() <= 5.0 {
+                    return toward(away);
+                }
+                if chance(0.15) {
+                    return self.random_direction();
+                }
+                IVec2::ZERO
+            }
+            Behavior::Flock => {
+                if !chance(0.3) {
+                    return IVec2::ZERO;
+                }
+                match self.flock_center(animal) {
+                    Some(center) if center.abs().max_element() > 2.0 => toward(center),
+                    _ => self.random_direction(),
+                }
+            }
+            Behavior::Roam => {
+                if animal.heading == IVec2::ZERO || chance(0.05) {
+                    animal.heading = self.random_direction();
+                }
+                if chance(0.6) {
+                    animal.heading
+                } else {
+                    IVec2::ZERO
+                }
+            }
+            Behavior::Wander => {
+                if chance(0.2) {
+                    return self.random_direction();
+                }
+                IVec2::ZERO
+            }
+        }
+    }
+
+    /// Finds the offset to the average position of nearby animals of the
+    /// same kind.
+    fn flock_center(&self, animal: &Animal) -> Option<Vec2> {
+        const RANGE: f32 = 8.0;
+        let (sum, count) = self
+            .animals
+            .values()
+            .filter(|other| other.kind == animal.kind && other.pos != animal.pos)
+            .map(|other| self.offset(animal.pos, other.pos))
+            .filter(|offset| offset.abs().max_element() <= RANGE)
+            .fold((Vec2::ZERO, 0), |(sum, count), offset| {
+                (sum + offset, count + 1)
+            });
+        (count > 0).then(|| (sum / count as f32).round())
+    }
+
+    /// Finds the offset to the closest prey within sight of a predator.
+    fn nearest_prey(&self, animal: &Animal) -> Option<Vec2> {
+        const RANGE: f32 = 12.0;
+        let prey = animal.kind.prey();
+        if prey.is_empty() {
+            return None;
+        }
+        self.animals
+            .values()
+            .filter(|other| prey.contains(&other.kind))
+            .map(|other| self.offset(animal.pos, other.pos))
+            .filter(|offset| offset.abs().max_element() <= RANGE)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+    }
+
+    fn random_direction(&self) -> IVec2 {
+        DIRECTIONS[self.rng.gen_range(0, DIRECTIONS.len())]
+    }
+}
+
+/// Reduces an offset to a single step along its longer axis.
+fn toward(offset: Vec2) -> IVec2 {
+    let offset = offset.as_ivec2();
+    if offset.x.abs() >= offset.y.abs() {
+        ivec2(offset.x.signum(), 0)
+    } else {
+        ivec2(0, offset.y.signum())
+    }
+}
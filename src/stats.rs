@@ -0,0 +1,89 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use macroquad::prelude::*;
+
+use crate::clock::Clock;
+use crate::weather::WeatherKind;
+use crate::world::{AnimalKind, ChunkGrid, Occupant, Plant, World};
+
+/// Counts of what lives in the loaded part of a world, for reports.
+#[derive(Clone, Debug, Serialize)]
+pub struct WorldStats {
+    /// Population by kind, including kinds that have died out.
+    pub animals: BTreeMap<AnimalKind, usize>,
+    pub chunks: usize,
+    pub day: u64,
+    /// Plants by kind, counted once each where they're rooted.
+    pub plants: BTreeMap<Plant, usize>,
+    /// Share of loaded tiles in the world with a plant rooted in them.
+    pub plant_cover: f32,
+    pub seed: u64,
+    /// Ticks run since the world began.
+    pub ticks: u64,
+    pub weather: WeatherKind,
+}
+
+impl World {
+    pub fn stats(&self) -> WorldStats {
+        let mut animals: BTreeMap<_, _> = AnimalKind::ALL.map(|kind| (kind, 0)).into();
+        for animal in self.animals.values() {
+            *animals.entry(animal.kind).or_default() += 1;
+        }
+        let mut plants: BTreeMap<_, _> = Plant::ALL.map(|plant| (plant, 0)).into();
+        let mut tiles = 0;
+        for (&coord, chunk) in &self.grid.chunks {
+            let chunk_start = coord * ChunkGrid::CHUNK_SIZE;
+            for x in 0..chunk.size_x() {
+                for y in 0..chunk.size_y() {
+                    // Chunks hang off the edges of bounded worlds.
+                    if !self.contains(chunk_start + ivec2(x as i32, y as i32)) {
+                        continue;
+                    }
+                    tiles += 1;
+                    if let Some(Occupant::Plant(plant)) = chunk.at(x, y).occupant {
+                        *plants.entry(plant).or_default() += 1;
+                    }
+                }
+            }
+        }
+        let rooted: usize = plants.values().sum();
+        WorldStats {
+            animals,
+            chunks: self.grid.chunks.len(),
+            day: self.clock.day(),
+            plants,
+            plant_cover: match tiles {
+                0 => 0.0,
+                _ => rooted as f32 / tiles as f32,
+            },
+            seed: self.seed,
+            ticks: self.clock.ticks.saturating_sub(Clock::new().ticks),
+            weather: self.weather.kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_count_ticks_run_and_tiles_in_world() {
+        // Smaller than the chunks around it, so they hang off the edges.
+        let size = ivec2(40, 30);
+        let mut world = World::generate(5, Some(size));
+        let player = world.center();
+        world.update_chunks(player);
+        assert_eq!(world.stats().ticks, 0);
+        for _ in 0..25 {
+            world.tick(player);
+        }
+        let stats = world.stats();
+        assert_eq!(stats.ticks, 25);
+        let rooted: usize = stats.plants.values().sum();
+        let tiles = (size.x * size.y) as f32;
+        assert!(rooted > 0);
+        assert_eq!(stats.plant_cover, rooted as f32 / tiles);
+    }
+}
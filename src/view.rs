@@ -0,0 +1,95 @@
+use macroquad::prelude::*;
+
+use greeny::map::block_color;
+use greeny::world::World;
+
+/// A down-sampled picture of the world around a center, kept in a texture
+/// and redrawn only when the view moves or goes stale.
+pub struct MapView {
+    /// World position at the middle of the view.
+    pub center: Vec2,
+    /// Map pixels across and down.
+    pub size: IVec2,
+    /// Tiles per map pixel.
+    pub zoom: f32,
+    /// What the texture shows, as snapped center, zoom and time drawn.
+    drawn: Option<(Vec2, f32, f64)>,
+    texture: Option<Texture2D>,
+}
+
+/// Seconds before redrawing an unmoved view, to pick up newly loaded chunks.
+const STALE_SECONDS: f64 = 2.0;
+
+impl MapView {
+    pub fn new(size: IVec2, zoom: f32) -> Self {
+        Self {
+            center: Vec2::ZERO,
+            size,
+            zoom,
+            drawn: None,
+            texture: None,
+        }
+    }
+
+    /// Moves the view by map pixels.
+    pub fn pan(&mut self, by: Vec2) {
+        self.center += by * self.zoom;
+    }
+
+    /// Where a world position lands on the map, in map pixels, which can be
+    /// outside the map.
+    pub fn place(&self, world: &World, pos: Vec2) -> Vec2 {
+        let offset = world.offset(self.snapped(), pos);
+        offset / self.zoom + self.size.as_vec2() * 0.5
+    }
+
+    pub fn texture(&self) -> Option<&Texture2D> {
+        self.texture.as_ref()
+    }
+
+    /// Redraws the texture if the view has moved or gone stale.
+    pub fn update(&mut self, world: &World) {
+        let center = self.snapped();
+        let now = get_time();
+        let fresh = self.drawn.is_some_and(|(drawn_center, zoom, time)| {
+            drawn_center == center && zoom == self.zoom && now - time < STALE_SECONDS
+        });
+        if fresh {
+            return;
+        }
+        let texture = Texture2D::from_image(&self.image(world));
+        texture.set_filter(FilterMode::Nearest);
+        self.texture = Some(texture);
+        self.drawn = Some((center, self.zoom, now));
+    }
+
+    /// Changes tiles per pixel by the factor, between a close up and the
+    /// whole world at once.
+    pub fn zoom_by(&mut self, factor: f32, world: &World) {
+        let most = match world.size {
+            Some(size) => (size.as_vec2() / self.size.as_vec2())
+                .max_element()
+                .max(1.0),
+            None => 64.0,
+        };
+        self.zoom = (self.zoom * factor).clamp(0.25, most);
+    }
+
+    fn image(&self, world: &World) -> Image {
+        let mut image = Image::gen_image_color(self.size.x as u16, self.size.y as u16, BLACK);
+        let start = self.snapped() - self.size.as_vec2() * 0.5 * self.zoom;
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let pos = start + vec2(x as f32, y as f32) * self.zoom;
+                let color = block_color(world, pos, self.zoom);
+                image.set_pixel(x as u32, y as u32, color);
+            }
+        }
+        image
+    }
+
+    /// Center on whole map pixels, so small moves don't redraw.
+    fn snapped(&self) -> Vec2 {
+        (self.center / self.zoom).floor() * self.zoom
+    }
+}
@@ -0,0 +1,99 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+use crate::clock::Clock;
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WeatherKind {
+    #[default]
+    Clear,
+    /// Dust storm on gust fronts, often ahead of monsoon rain.
+    Haboob,
+    /// Summer thunderstorm with heavy rain.
+    Monsoon,
+}
+
+/// Weather as a state machine, with each storm rising and falling over its
+/// duration.
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Weather {
+    pub duration: u32,
+    pub elapsed: u32,
+    pub kind: WeatherKind,
+    /// How recently and how much it rained, from 0 for bone dry to 1.
+    pub wetness: f32,
+}
+
+impl Weather {
+    pub fn advance(&mut self, rng: &RandGenerator) {
+        self.elapsed = self.elapsed.saturating_add(1);
+        if self.kind == WeatherKind::Monsoon {
+            self.wetness = (self.wetness + 0.004 * self.intensity()).min(1.0);
+        } else {
+            // Dry out over a few days.
+            self.wetness = (self.wetness - 1.0 / (3 * Clock::DAY_TICKS) as f32).max(0.0);
+        }
+        let next = match self.kind {
+            WeatherKind::Clear => match rng.gen_range(0.0, 1.0) {
+                n if n < 1.0 / 3000.0 => WeatherKind::Monsoon,
+                n if n < 1.0 / 3000.0 + 1.0 / 5000.0 => WeatherKind::Haboob,
+                _ => return,
+            },
+            _ if self.elapsed < self.duration => return,
+            // Half of dust storms blow in ahead of rain.
+            WeatherKind::Haboob if rng.gen_range(0.0, 1.0) < 0.5 => WeatherKind::Monsoon,
+            _ => WeatherKind::Clear,
+        };
+        self.start(next, rng);
+    }
+
+    /// Multiplier on how much animals move about, as they shelter from storms.
+    pub fn activity(&self) -> f32 {
+        let sheltering = match self.kind {
+            WeatherKind::Clear => 0.0,
+            WeatherKind::Haboob => 0.9,
+            WeatherKind::Monsoon => 0.8,
+        };
+        1.0 - sheltering * self.intensity()
+    }
+
+    /// Strength of the current weather, easing in and out from 0 to 1.
+    pub fn intensity(&self) -> f32 {
+        match self.kind {
+            WeatherKind::Clear => 0.0,
+            _ => {
+                let progress = self.elapsed as f32 / self.duration.max(1) as f32;
+                // Quick to build, but holding strong through the middle.
+                (progress.min(1.0) * PI).sin().sqrt()
+            }
+        }
+    }
+
+    /// Ocotillos leaf out for a while after good rain.
+    pub fn leafed_out(&self) -> bool {
+        self.wetness > 0.3
+    }
+
+    pub fn start(&mut self, kind: WeatherKind, rng: &RandGenerator) {
+        self.kind = kind;
+        self.elapsed = 0;
+        self.duration = match kind {
+            WeatherKind::Clear => 0,
+            WeatherKind::Haboob => rng.gen_range(200, 500),
+            WeatherKind::Monsoon => rng.gen_range(300, 900),
+        };
+    }
+
+    /// Color laid over the scene to hide distant things, with alpha for
+    /// strength.
+    pub fn veil(&self) -> Color {
+        let intensity = self.intensity();
+        match self.kind {
+            WeatherKind::Clear => BLANK,
+            WeatherKind::Haboob => Color::new(0.62, 0.45, 0.3, 0.7 * intensity),
+            WeatherKind::Monsoon => Color::new(0.25, 0.28, 0.35, 0.4 * intensity),
+        }
+    }
+}
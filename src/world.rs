@@ -1,55 +1,378 @@
+use crate::arena::{Arena, Handle};
+use crate::biome::{Biome, pick};
+use crate::clock::Clock;
+use crate::explore::Explored;
 use crate::info::GameMetrics;
+use crate::noise::hash2;
+use crate::weather::Weather;
 use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Mixed into per-tile plant seeds, to keep them apart from other uses of
+/// the same position hash.
+const PLANT_SALT: u64 = 0x9A6E_7C1D_5B3F_0E21;
+
+/// What lies past the bounds of a sized world.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Edge {
+    /// Nothing can cross the border.
+    #[default]
+    Solid,
+    /// Crossing one border comes back in at the opposite one.
+    Wrap,
+}
 
 pub struct World {
-    pub animals: Vec<Animal>,
-    pub grid: Grid<Tile>,
+    pub animals: Arena<Animal>,
+    pub clock: Clock,
+    pub edge: Edge,
+    pub explored: Explored,
+    pub grid: ChunkGrid,
+    /// Drives simulation, separately from chunk generation.
+    pub rng: RandGenerator,
+    pub seed: u64,
+    /// Bounds from the origin, or `None` for an unbounded world.
+    pub size: Option<IVec2>,
+    pub weather: Weather,
 }
 
 impl World {
-    pub fn new() -> Self {
+    pub const DEFAULT_SIZE: IVec2 = IVec2::new(1500, 1100);
+    /// Chunks within this many chunks of the center are kept loaded.
+    pub const LOAD_RADIUS: i32 = 1;
+    /// Chunks beyond this many chunks of the center are evicted.
+    pub const EVICT_RADIUS: i32 = 2;
+
+    /// Prepares a world from the given seed, so the same seed and size always
+    /// give the same tiles and animals. Chunks are generated only as needed.
+    pub fn generate(seed: u64, size: Option<IVec2>) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(hash2(seed, i32::MAX, i32::MAX));
+        Self {
+            animals: Arena::new(),
+            clock: Clock::new(),
+            edge: Edge::default(),
+            explored: Explored::default(),
+            grid: ChunkGrid::default(),
+            rng,
+            seed,
+            size,
+            weather: Weather::default(),
+        }
+    }
+
+    pub fn biome_at(&self, pos: IVec2) -> Biome {
+        Biome::at(self.seed, pos)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        match self.size {
+            Some(size) => Vec2::floor(size.as_vec2() * 0.5),
+            None => Vec2::ZERO,
+        }
+    }
+
+    pub fn contains(&self, pos: IVec2) -> bool {
+        match self.size {
+            Some(size) => pos.cmpge(IVec2::ZERO).all() && pos.cmplt(size).all(),
+            None => true,
+        }
+    }
+
+    /// Finds the shortest offset between positions, including across the
+    /// edges of a wrapping world.
+    pub fn offset(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let offset = to - from;
+        match (self.edge, self.size) {
+            (Edge::Wrap, Some(size)) => {
+                let size = size.as_vec2();
+                offset - (offset / size).round() * size
+            }
+            _ => offset,
+        }
+    }
+
+    /// Returns where a step from the position lands, if that spot is open.
+    /// Diagonal steps can't squeeze between two things blocking the corner.
+    pub fn step(&self, pos: Vec2, by: Vec2) -> Option<Vec2> {
+        let next = pos + by;
+        let pinched = by.x != 0.0
+            && by.y != 0.0
+            && self.occupied(pos + vec2(by.x, 0.0))
+            && self.occupied(pos + vec2(0.0, by.y));
+        match pinched || self.occupied(next) {
+            true => None,
+            false => Some(self.wrap(next.floor().as_ivec2()).as_vec2()),
+        }
+    }
+
+    /// Returns the tile at the position, or `None` past a solid edge.
+    pub fn tile(&self, pos: IVec2) -> Option<Tile> {
+        let pos = self.wrap(pos);
+        self.contains(pos).then(|| self.grid.at(pos))
+    }
+
+    /// Loads chunks near the center and evicts far ones, along with their
+    /// animals.
+    pub fn update_chunks(&mut self, center: Vec2) {
+        let size = ChunkGrid::CHUNK_SIZE;
+        let center = center.floor();
+        let far = |world: &Self, coord: IVec2| {
+            let chunk_center = ((coord * size).as_vec2() + size as f32 * 0.5).floor();
+            let offset = world.offset(center, chunk_center).abs().max_element();
+            offset > (Self::EVICT_RADIUS as f32 + 0.5) * size as f32
+        };
+        let evicted: Vec<IVec2> = self
+            .grid
+            .chunks
+            .keys()
+            .copied()
+            .filter(|&coord| far(self, coord))
+            .collect();
+        if !evicted.is_empty() {
+            for coord in evicted {
+                self.grid.chunks.remove(&coord);
+            }
+            let grid = &self.grid;
+            self.animals
+                .retain(|_, animal| grid.is_loaded(animal.pos.floor().as_ivec2()));
+        }
+        self.load_chunks(center, Self::LOAD_RADIUS);
+    }
+
+    /// Loads any chunks within `radius` chunks of the center that aren't
+    /// already.
+    pub fn load_chunks(&mut self, center: Vec2, radius: i32) {
+        let size = ChunkGrid::CHUNK_SIZE;
+        let center = center.floor();
+        // Step by half chunks so wrapping past a narrower last chunk can't skip
+        // over it.
+        let reach = radius * 2;
+        for x in -reach..=reach {
+            for y in -reach..=reach {
+                let pos = center.as_ivec2() + ivec2(x, y) * size / 2;
+                let pos = self.wrap(pos);
+                if !self.contains(pos) {
+                    continue;
+                }
+                let coord = ChunkGrid::chunk_coord(pos);
+                if !self.grid.chunks.contains_key(&coord) {
+                    self.load_chunk(coord);
+                }
+            }
+        }
+    }
+
+    /// Brings positions past the edges of a wrapping world back inside it.
+    pub fn wrap(&self, pos: IVec2) -> IVec2 {
+        match (self.edge, self.size) {
+            (Edge::Wrap, Some(size)) => pos.rem_euclid(size),
+            _ => pos,
+        }
+    }
+
+    /// Generates the tiles of a chunk along with the animals that start in
+    /// it, all derived from the seed and chunk coordinate alone.
+    pub fn generate_chunk(&self, coord: IVec2) -> (Grid<Tile>, Vec<Animal>) {
+        let rng = RandGenerator::new();
+        rng.srand(hash2(self.seed, coord.x, coord.y));
+        let chunk_start = coord * ChunkGrid::CHUNK_SIZE;
         // Base grid, including plants.
-        let mut grid = Grid::new_sized(1500, 1100);
-        for x in 0..grid.size_x() {
-            for y in 0..grid.size_y() {
-                grid.set_at(x, y, Tile::rand());
+        let size = ChunkGrid::CHUNK_SIZE as usize;
+        let mut chunk = Grid::new_sized(size, size);
+        // Tiles under any plant's footprint, so animals start clear of them.
+        let mut covered = Grid::new_sized(size, size);
+        let local = |pos: IVec2| {
+            let local = pos - chunk_start;
+            let inside =
+                local.cmpge(IVec2::ZERO).all() && local.cmplt(IVec2::splat(size as i32)).all();
+            inside.then_some((local.x as usize, local.y as usize))
+        };
+        for (root, plant) in self.plants_near(chunk_start) {
+            for &offset in plant.footprint() {
+                if let Some((x, y)) = local(root + offset) {
+                    covered.set_at(x, y, true);
+                }
+            }
+            if let Some((x, y)) = local(root) {
+                chunk.set_at(
+                    x,
+                    y,
+                    Tile {
+                        occupant: Some(Occupant::Plant(plant)),
+                    },
+                );
             }
         }
-        // Animals.
-        let animals = vec![];
-        let mut selfish = Self { animals, grid };
+        let mut inside = vec![];
+        for x in 0..size {
+            for y in 0..size {
+                let pos = chunk_start + ivec2(x as i32, y as i32);
+                if self.contains(pos) {
+                    inside.push(pos);
+                }
+            }
+        }
+        // Animals, at the same density as one animal per screen.
         let metrics = GameMetrics::default();
-        let area_size = (metrics.ground_size.x * metrics.ground_size.y) as usize;
-        let animal_count = selfish.grid.size_x() * selfish.grid.size_y() / area_size;
-        // dbg!(animal_count);
-        while selfish.animals.len() < animal_count {
-            let x = rand::rand() as usize % selfish.grid.size_x();
-            let y = rand::rand() as usize % selfish.grid.size_y();
-            let pos = Vec2::new(x as f32, y as f32);
-            if !selfish.occupied(pos) {
-                let animal = Animal {
-                    kind: AnimalKind::rand(),
-                    pos,
+        let area_size = metrics.ground_size.x * metrics.ground_size.y;
+        let expected = inside.len() as f32 / area_size;
+        let mut animal_count = expected as usize;
+        if rng.gen_range(0.0, 1.0) < expected.fract() {
+            animal_count += 1;
+        }
+        let mut animals: Vec<Animal> = vec![];
+        // Limit tries in case the chunk is crowded.
+        for _ in 0..animal_count * 10 {
+            if animals.len() == animal_count {
+                break;
+            }
+            let pos = inside[rng.gen_range(0, inside.len())];
+            let kind = AnimalKind::rand(&rng, self.biome_at(pos));
+            let local = pos - chunk_start;
+            let open = !covered.at(local.x as usize, local.y as usize);
+            if open && !animals.iter().any(|animal| animal.pos == pos.as_vec2()) {
+                animals.push(Animal::new(kind, pos.as_vec2()));
+            }
+        }
+        (chunk, animals)
+    }
+
+    /// Plants rooted in or reaching into the chunk starting at a position.
+    /// Each is decided from the seed and the plants that would grow around
+    /// it alone, so the same ones grow whichever chunk asks, and footprints
+    /// can cross chunk edges.
+    fn plants_near(&self, chunk_start: IVec2) -> Vec<(IVec2, Plant)> {
+        let reach = Plant::ALL
+            .iter()
+            .flat_map(|plant| plant.footprint())
+            .fold(IVec2::ZERO, |reach, offset| reach.max(offset.abs()));
+        // Roots whose footprints might reach the chunk, along with the seeds
+        // near enough to crowd them.
+        let roots_start = chunk_start - reach;
+        let roots_end = chunk_start + ChunkGrid::CHUNK_SIZE + reach;
+        let start = roots_start - 2 * reach;
+        let area = (roots_end + 2 * reach - start).as_uvec2();
+        let mut seeds = Grid::new_sized(area.x as usize, area.y as usize);
+        for x in 0..area.x as usize {
+            for y in 0..area.y as usize {
+                seeds.set_at(x, y, self.plant_seed(start + ivec2(x as i32, y as i32)));
+            }
+        }
+        let seed_at = |pos: IVec2| {
+            let local = pos - start;
+            seeds.at(local.x as usize, local.y as usize)
+        };
+        let mut plants = vec![];
+        for y in roots_start.y..roots_end.y {
+            for x in roots_start.x..roots_end.x {
+                let root = ivec2(x, y);
+                let Some((plant, rank)) = seed_at(root) else {
+                    continue;
                 };
-                let animal_idx = selfish.animals.len();
-                selfish.grid.mut_at(x, y).occupant = Some(Occupant::Animal(animal_idx));
-                selfish.animals.push(animal);
+                let fits = plant
+                    .footprint()
+                    .iter()
+                    .all(|&offset| self.contains(root + offset));
+                // Yield to any higher ranked seed in the way, whether or not
+                // it grows, so the answer never depends on farther seeds.
+                let crowded = (-2 * reach.y..=2 * reach.y)
+                    .flat_map(|dy| (-2 * reach.x..=2 * reach.x).map(move |dx| ivec2(dx, dy)))
+                    .filter(|&offset| offset != IVec2::ZERO)
+                    .any(|offset| {
+                        let other = root + offset;
+                        seed_at(other).is_some_and(|(other_plant, other_rank)| {
+                            (other_rank, other.x, other.y) > (rank, root.x, root.y)
+                                && plant.footprint().iter().any(|&a| {
+                                    other_plant
+                                        .footprint()
+                                        .iter()
+                                        .any(|&b| root + a == other + b)
+                                })
+                        })
+                    });
+                if fits && !crowded {
+                    plants.push((root, plant));
+                }
             }
         }
-        selfish
+        plants
+    }
+
+    /// The plant that would root at a tile given room, and its rank for
+    /// getting room, from the seed and position alone.
+    fn plant_seed(&self, pos: IVec2) -> Option<(Plant, u32)> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let rng = RandGenerator::new();
+        rng.srand(hash2(self.seed, pos.x, pos.y) ^ PLANT_SALT);
+        let plant = Plant::rand(&rng, self.biome_at(pos))?;
+        Some((plant, rng.rand()))
     }
 
+    fn load_chunk(&mut self, coord: IVec2) {
+        let (chunk, animals) = self.generate_chunk(coord);
+        self.grid.chunks.insert(coord, chunk);
+        for animal in animals {
+            self.spawn_animal(animal.kind, animal.pos.as_ivec2());
+        }
+    }
+
+    /// Checks for plants or live animals, ignoring any stale animal handles.
+    /// Anything past a solid edge counts as occupied.
     pub fn occupied(&self, vec: Vec2) -> bool {
-        self.grid
-            .at(vec.x as usize, vec.y as usize)
-            .occupant
-            .is_some()
+        let Some(tile) = self.tile(vec.floor().as_ivec2()) else {
+            return true;
+        };
+        match tile.occupant {
+            Some(Occupant::Animal(handle)) => self.animals.contains(handle),
+            Some(Occupant::Plant(_)) => true,
+            None => self.plant_at(vec.floor().as_ivec2()).is_some(),
+        }
     }
-}
 
-impl Default for World {
-    fn default() -> Self {
-        Self::new()
+    /// Finds the plant whose footprint covers the position, along with the
+    /// tile it's rooted in.
+    pub fn plant_at(&self, pos: IVec2) -> Option<(IVec2, Plant)> {
+        for plant in Plant::ALL {
+            for &offset in plant.footprint() {
+                let root = pos - offset;
+                if let Some(Tile {
+                    occupant: Some(Occupant::Plant(found)),
+                }) = self.tile(root)
+                    && found == plant
+                {
+                    return Some((self.wrap(root), plant));
+                }
+            }
+        }
+        None
+    }
+
+    /// Removes the animal and its occupancy, if the handle is still current.
+    pub fn remove_animal(&mut self, handle: AnimalHandle) -> Option<Animal> {
+        let animal = self.animals.remove(handle)?;
+        if let Some(tile) = self.grid.mut_at(animal.pos.as_ivec2())
+            && tile.occupant == Some(Occupant::Animal(handle))
+        {
+            tile.occupant = None;
+        }
+        Some(animal)
+    }
+
+    /// Adds an animal on an open, loaded tile, returning its handle.
+    pub fn spawn_animal(&mut self, kind: AnimalKind, pos: IVec2) -> Option<AnimalHandle> {
+        let pos = self.wrap(pos);
+        if self.occupied(pos.as_vec2()) {
+            return None;
+        }
+        let tile = self.grid.mut_at(pos)?;
+        let handle = self.animals.insert(Animal::new(kind, pos.as_vec2()));
+        tile.occupant = Some(Occupant::Animal(handle));
+        Some(handle)
     }
 }
 
@@ -75,6 +398,11 @@ where
         self.values[x * self.size_y + y]
     }
 
+    /// Returns the value if the position is inside the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        (x < self.size_x && y < self.size_y).then(|| self.at(x, y))
+    }
+
     pub fn mut_at(&mut self, x: usize, y: usize) -> &mut T {
         &mut self.values[x * self.size_y + y]
     }
@@ -96,13 +424,50 @@ where
     }
 }
 
+/// Tiles stored in fixed-size chunks that come and go around the player.
+#[derive(Default)]
+pub struct ChunkGrid {
+    pub chunks: HashMap<IVec2, Grid<Tile>>,
+}
+
+impl ChunkGrid {
+    pub const CHUNK_SIZE: i32 = 32;
+
+    pub fn chunk_coord(pos: IVec2) -> IVec2 {
+        pos.div_euclid(IVec2::splat(Self::CHUNK_SIZE))
+    }
+
+    /// Returns the tile at the position, or an empty tile if not loaded.
+    pub fn at(&self, pos: IVec2) -> Tile {
+        self.get(pos).unwrap_or_default()
+    }
+
+    /// Returns the tile at the position if its chunk is loaded.
+    pub fn get(&self, pos: IVec2) -> Option<Tile> {
+        let chunk = self.chunks.get(&Self::chunk_coord(pos))?;
+        let local = pos.rem_euclid(IVec2::splat(Self::CHUNK_SIZE));
+        chunk.get(local.x as usize, local.y as usize)
+    }
+
+    pub fn is_loaded(&self, pos: IVec2) -> bool {
+        self.chunks.contains_key(&Self::chunk_coord(pos))
+    }
+
+    pub fn mut_at(&mut self, pos: IVec2) -> Option<&mut Tile> {
+        self.chunks.get_mut(&Self::chunk_coord(pos)).map(|chunk| {
+            let local = pos.rem_euclid(IVec2::splat(Self::CHUNK_SIZE));
+            chunk.mut_at(local.x as usize, local.y as usize)
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Occupant {
-    Animal(usize),
+    Animal(AnimalHandle),
     Plant(Plant),
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum AnimalKind {
     Bead,
     Bob,
@@ -119,28 +484,52 @@ pub enum AnimalKind {
 }
 
 impl AnimalKind {
-    pub fn rand() -> Self {
-        let animals = [
-            AnimalKind::Bead,
-            AnimalKind::Bob,
-            AnimalKind::Coyote,
-            AnimalKind::Jack,
-            AnimalKind::Javelina,
-            AnimalKind::Rattler,
-            AnimalKind::Runner,
-            AnimalKind::Turkey,
-        ];
-        animals[rand::rand() as usize % animals.len()]
+    /// Every kind, in declaration order.
+    pub const ALL: [AnimalKind; 8] = [
+        AnimalKind::Bead,
+        AnimalKind::Bob,
+        AnimalKind::Coyote,
+        AnimalKind::Jack,
+        AnimalKind::Javelina,
+        AnimalKind::Rattler,
+        AnimalKind::Runner,
+        AnimalKind::Turkey,
+    ];
+
+    pub fn rand(rng: &RandGenerator, biome: Biome) -> Self {
+        let odds = biome.animal_odds();
+        let total = odds.iter().map(|(_, chance)| chance).sum();
+        pick(rng, odds, total).unwrap_or(odds[0].0)
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+pub type AnimalHandle = Handle;
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Animal {
     pub kind: AnimalKind,
     pub pos: Vec2,
+    /// Direction of travel for animals that keep going once started.
+    pub heading: IVec2,
+    /// Ticks left before moving again.
+    pub rest: u32,
+    /// Ticks since last eating, for predators.
+    pub hunger: u32,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+impl Animal {
+    pub fn new(kind: AnimalKind, pos: Vec2) -> Self {
+        Self {
+            kind,
+            pos,
+            heading: IVec2::ZERO,
+            rest: 0,
+            hunger: 0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum Plant {
     NopalBig,
     NopalSmall,
@@ -149,16 +538,53 @@ pub enum Plant {
 }
 
 impl Plant {
-    pub fn rand() -> Option<Self> {
-        let n = rand::gen_range(0.0, 1.0);
-        let plant = match () {
-            _ if n < 0.8 => return None,
-            _ if n < 0.87 => Plant::NopalSmall,
-            _ if n < 0.94 => Plant::NopalBig,
-            _ if n < 0.98 => Plant::Ocotillo,
-            _ => Plant::Saguaro,
-        };
-        Some(plant)
+    /// Every kind, in declaration order.
+    pub const ALL: [Plant; 4] = [
+        Plant::NopalBig,
+        Plant::NopalSmall,
+        Plant::Ocotillo,
+        Plant::Saguaro,
+    ];
+
+    /// Tiles the plant blocks, relative to the tile it's rooted in. These
+    /// follow what the sprite covers, so nothing walks through arms or pads.
+    pub fn footprint(self) -> &'static [IVec2] {
+        const NOPAL_BIG: [IVec2; 4] = [
+            IVec2::new(0, 0),
+            IVec2::new(-1, -1),
+            IVec2::new(0, -1),
+            IVec2::new(1, -1),
+        ];
+        const NOPAL_SMALL: [IVec2; 1] = [IVec2::new(0, 0)];
+        const OCOTILLO: [IVec2; 7] = [
+            IVec2::new(0, 0),
+            IVec2::new(-1, -1),
+            IVec2::new(0, -1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, -2),
+            IVec2::new(0, -2),
+            IVec2::new(1, -2),
+        ];
+        const SAGUARO: [IVec2; 8] = [
+            IVec2::new(0, 0),
+            IVec2::new(0, -1),
+            IVec2::new(0, -2),
+            IVec2::new(1, -2),
+            IVec2::new(-1, -3),
+            IVec2::new(0, -3),
+            IVec2::new(1, -3),
+            IVec2::new(0, -4),
+        ];
+        match self {
+            Plant::NopalBig => &NOPAL_BIG,
+            Plant::NopalSmall => &NOPAL_SMALL,
+            Plant::Ocotillo => &OCOTILLO,
+            Plant::Saguaro => &SAGUARO,
+        }
+    }
+
+    pub fn rand(rng: &RandGenerator, biome: Biome) -> Option<Self> {
+        pick(rng, biome.plant_odds(), 1.0)
     }
 }
 
@@ -167,10 +593,133 @@ pub struct Tile {
     pub occupant: Option<Occupant>,
 }
 
-impl Tile {
-    pub fn rand() -> Self {
-        Self {
-            occupant: Plant::rand().map(|x| Occupant::Plant(x)),
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Plants by tile and animals by place, for comparing generated chunks.
+    fn generated(world: &World, coord: IVec2) -> (Vec<Option<Occupant>>, Vec<(AnimalKind, Vec2)>) {
+        let (chunk, animals) = world.generate_chunk(coord);
+        let size = ChunkGrid::CHUNK_SIZE as usize;
+        let tiles = (0..size * size)
+            .map(|index| chunk.at(index / size, index % size).occupant)
+            .collect();
+        let animals = animals
+            .iter()
+            .map(|animal| (animal.kind, animal.pos))
+            .collect();
+        (tiles, animals)
+    }
+
+    #[test]
+    fn same_seed_generates_same_chunks() {
+        let coords = [ivec2(0, 0), ivec2(3, 1), ivec2(10, 20), ivec2(46, 34)];
+        let a = World::generate(42, Some(World::DEFAULT_SIZE));
+        let b = World::generate(42, Some(World::DEFAULT_SIZE));
+        let other = World::generate(43, Some(World::DEFAULT_SIZE));
+        let mut differs = false;
+        for coord in coords {
+            let expected = generated(&a, coord);
+            assert_eq!(expected, generated(&b, coord), "chunk {coord}");
+            assert!(expected.0.iter().any(Option::is_some), "chunk {coord}");
+            differs |= expected != generated(&other, coord);
+        }
+        assert!(differs);
+    }
+
+    /// An 8 by 6 world with one empty chunk loaded and small plants placed,
+    /// so only they and the edges get in the way.
+    pub(crate) fn small_world(edge: Edge, plants: &[IVec2]) -> World {
+        let mut world = World::generate(1, Some(ivec2(8, 6)));
+        world.edge = edge;
+        let size = ChunkGrid::CHUNK_SIZE as usize;
+        world
+            .grid
+            .chunks
+            .insert(IVec2::ZERO, Grid::new_sized(size, size));
+        for &pos in plants {
+            *world.grid.mut_at(pos).unwrap() = Tile {
+                occupant: Some(Occupant::Plant(Plant::NopalSmall)),
+            };
+        }
+        world
+    }
+
+    /// Steps off every edge and corner, as (from, by, wrapped landing).
+    const EDGE_STEPS: [(IVec2, IVec2, IVec2); 8] = [
+        (IVec2::new(0, 3), IVec2::new(-1, 0), IVec2::new(7, 3)),
+        (IVec2::new(7, 3), IVec2::new(1, 0), IVec2::new(0, 3)),
+        (IVec2::new(4, 0), IVec2::new(0, -1), IVec2::new(4, 5)),
+        (IVec2::new(4, 5), IVec2::new(0, 1), IVec2::new(4, 0)),
+        (IVec2::new(0, 0), IVec2::new(-1, -1), IVec2::new(7, 5)),
+        (IVec2::new(7, 5), IVec2::new(1, 1), IVec2::new(0, 0)),
+        (IVec2::new(7, 0), IVec2::new(1, -1), IVec2::new(0, 5)),
+        (IVec2::new(0, 5), IVec2::new(-1, 1), IVec2::new(7, 0)),
+    ];
+
+    #[test]
+    fn solid_edges_block_steps() {
+        let world = small_world(Edge::Solid, &[]);
+        for (from, by, _) in EDGE_STEPS {
+            assert_eq!(
+                world.step(from.as_vec2(), by.as_vec2()),
+                None,
+                "{from} by {by}"
+            );
+            assert!(world.tile(from + by).is_none());
+        }
+        assert!(world.step(vec2(0.0, 3.0), vec2(1.0, 0.0)).is_some());
+    }
+
+    #[test]
+    fn wrap_edges_land_on_opposite_border() {
+        let world = small_world(Edge::Wrap, &[]);
+        for (from, by, to) in EDGE_STEPS {
+            let landed = world.step(from.as_vec2(), by.as_vec2());
+            assert_eq!(landed, Some(to.as_vec2()), "{from} by {by}");
+            assert!(world.tile(from + by).is_some());
+        }
+    }
+
+    #[test]
+    fn plants_ignore_chunk_edges() {
+        let world = World::generate(7, Some(World::DEFAULT_SIZE));
+        let size = ChunkGrid::CHUNK_SIZE;
+        let mut roots = vec![];
+        for cy in 0..8 {
+            for cx in 0..8 {
+                let (chunk, _) = world.generate_chunk(ivec2(cx, cy));
+                for x in 0..size {
+                    for y in 0..size {
+                        if let Some(Occupant::Plant(plant)) =
+                            chunk.at(x as usize, y as usize).occupant
+                        {
+                            roots.push((ivec2(cx, cy) * size + ivec2(x, y), plant));
+                        }
+                    }
+                }
+            }
+        }
+        // Big plants grow right up to chunk edges.
+        let mut rows = [0; ChunkGrid::CHUNK_SIZE as usize];
+        let mut columns = [0; ChunkGrid::CHUNK_SIZE as usize];
+        for &(root, plant) in &roots {
+            if plant.footprint().len() > 1 {
+                rows[root.y.rem_euclid(size) as usize] += 1;
+                columns[root.x.rem_euclid(size) as usize] += 1;
+            }
+        }
+        assert!(
+            rows.iter().chain(&columns).all(|&count| count > 0),
+            "{rows:?} {columns:?}"
+        );
+        // Footprints never overlap, even across chunks.
+        let mut covered = HashMap::new();
+        for (root, plant) in roots {
+            for &offset in plant.footprint() {
+                let old = covered.insert(root + offset, root);
+                assert_eq!(old, None, "{plant:?} at {root} overlaps");
+            }
         }
     }
 }